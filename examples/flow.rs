@@ -9,9 +9,11 @@
 //! - FORDEFI_ADDRESS: Wallet address (optional, auto-discovered if not set)
 
 use std::io::{self, Write};
+use std::time::Duration;
 
 use ostium_sdk::{
-    get_btc_price, get_eth_price, CloseTradeParams, DepositParams, FordefiSigner,
+    constants::{scale_to_decimals, unscale_from_decimals},
+    get_btc_price, get_eth_price, DepositParams, FordefiSigner,
     NetworkConfig, OstiumClient, PlaceOrderParams, Position, TransactionSigner,
 };
 
@@ -41,10 +43,23 @@ async fn main() -> eyre::Result<()> {
         let address: alloy::primitives::Address = address_str
             .parse()
             .expect("FORDEFI_ADDRESS must be a valid address");
-        FordefiSigner::new(&jwt_token, &private_key_pem, address, &config.rpc_url).await?
+        FordefiSigner::new_with_http_config(
+            &jwt_token,
+            &private_key_pem,
+            address,
+            &config.rpc_url,
+            &config.http,
+        )
+        .await?
     } else {
         println!("FORDEFI_ADDRESS not set, discovering vault...");
-        FordefiSigner::discover(&jwt_token, &private_key_pem, &config.rpc_url).await?
+        FordefiSigner::discover_with_http_config(
+            &jwt_token,
+            &private_key_pem,
+            &config.rpc_url,
+            &config.http,
+        )
+        .await?
     };
 
     let client = OstiumClient::new(signer, config).await?;
@@ -123,28 +138,25 @@ async fn long_btc_flow<S: ostium_sdk::TransactionSigner>(
 
     println!("Placing LONG ${:.0} position...", collateral * leverage);
 
-    let params = PlaceOrderParams::market(0, collateral, leverage, true) // pair_index 0 = BTC
+    let params = PlaceOrderParams::market_symbol("BTC", "USD", collateral, leverage, true)
         .with_open_price(current_price)
         .with_slippage(2.0);
 
-    let tx_hash = client.place_order(params, None).await?;
-    println!("Transaction: {}", tx_hash);
-
-    let receipt = client.wait_for_receipt(tx_hash).await?;
-    if receipt.status() {
-        println!("LONG trade placed successfully!");
-
-        // Show positions after
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-        let positions_after = client.get_positions(None).await?;
-        println!("\nPositions AFTER: {}", positions_after.len());
-        if !positions_after.is_empty() {
-            print_positions(&positions_after);
-        }
-    } else {
-        println!("Trade transaction reverted!");
+    if let Ok(fees) = client.estimate_trade_fees(&params).await {
+        println!(
+            "Estimated fees: open ${:.4}, close ${:.4} ({:.4}% of notional)",
+            fees.open_fee, fees.close_fee, fees.fee_percent
+        );
+        println!("Effective collateral after open fee: ${:.4}", fees.effective_collateral);
     }
 
+    // Submits, waits for the receipt, then waits for the oracle to fill the order
+    let position = client
+        .open_and_wait(params, None, Duration::from_secs(120))
+        .await?;
+    println!("LONG trade opened! Trade ID: {}", position.trade_id);
+    print_positions(&[position]);
+
     Ok(())
 }
 
@@ -175,13 +187,13 @@ async fn view_info<S: ostium_sdk::TransactionSigner>(
     }
 
     // Display OLP balance
+    let share_decimals = olp_result.as_ref().map_or(6, |olp_pos| olp_pos.share_decimals);
     if let Ok(olp_pos) = olp_result {
-        let shares_f64 = olp_pos.shares.to_string().parse::<f64>().unwrap_or(0.0) / 1e6;
-        println!("OLP Shares: {:.6} (${:.2})", shares_f64, olp_pos.value);
+        println!("OLP Shares: {:.6} (${:.2})", olp_pos.shares_f64(), olp_pos.value);
     }
 
     // Fetch and display pending withdrawals in parallel
-    if client.config().vault.is_some() {
+    if client.has_vault() {
         if let Ok(epoch_info) = epoch_result {
             let current = epoch_info.current_epoch as u16;
             let start_epoch = current.saturating_sub(10);
@@ -189,7 +201,6 @@ async fn view_info<S: ostium_sdk::TransactionSigner>(
             // Fetch all pending withdrawals in parallel
             let futures: Vec<_> = (start_epoch..=current + 1)
                 .map(|epoch| {
-                    let client = client;
                     async move { (epoch, client.get_pending_withdrawal(epoch).await) }
                 })
                 .collect();
@@ -198,7 +209,7 @@ async fn view_info<S: ostium_sdk::TransactionSigner>(
 
             for (epoch, result) in results {
                 if let Ok(pending) = result {
-                    let pending_f64: f64 = pending.to_string().parse().unwrap_or(0.0) / 1e6;
+                    let pending_f64 = unscale_from_decimals(pending, share_decimals);
                     if pending_f64 > 0.0 {
                         println!("Pending Withdrawal (Epoch {}): {:.6} OLP", epoch, pending_f64);
                     }
@@ -300,11 +311,10 @@ async fn close_position_flow<S: ostium_sdk::TransactionSigner>(
     );
     println!("Current price: ${:.2}", market_price);
 
-    let params = CloseTradeParams::close_all(position.pair_index, trade_index, market_price);
-    let tx_hash = client.close_trade(params).await?;
-    println!("Transaction: {}", tx_hash);
+    let pending = client.close_trade_pending(position.to_close_params(market_price)).await?;
+    println!("Transaction: {}", pending.hash());
 
-    let receipt = client.wait_for_receipt(tx_hash).await?;
+    let receipt = pending.await_receipt().await?;
     if receipt.status() {
         println!("Position closed successfully!");
 
@@ -331,7 +341,7 @@ async fn deposit_olp_flow<S: ostium_sdk::TransactionSigner>(
     println!("\n=== Deposit to OLP Vault ===");
 
     // Check if vault is configured
-    if client.config().vault.is_none() {
+    if !client.has_vault() {
         println!("OLP Vault is not configured for this network.");
         return Ok(());
     }
@@ -351,7 +361,7 @@ async fn deposit_olp_flow<S: ostium_sdk::TransactionSigner>(
     };
     let usdc_balance = usdc_result?;
 
-    let shares_before = balance_before.shares.to_string().parse::<f64>().unwrap_or(0.0) / 1e6;
+    let shares_before = balance_before.shares_f64();
     println!("\nOLP Position BEFORE deposit:");
     println!("  Shares: {:.6}", shares_before);
     println!("  Value: ${:.2}", balance_before.value);
@@ -371,10 +381,10 @@ async fn deposit_olp_flow<S: ostium_sdk::TransactionSigner>(
 
     println!("\nDepositing ${:.2} USDC...", amount);
     let params = DepositParams::new(amount);
-    let tx_hash = client.deposit_olp(params).await?;
-    println!("Transaction: {}", tx_hash);
+    let pending = client.deposit_olp_pending(params).await?;
+    println!("Transaction: {}", pending.hash());
 
-    let receipt = client.wait_for_receipt(tx_hash).await?;
+    let receipt = pending.await_receipt().await?;
     if receipt.status() {
         println!("Deposit successful!");
     } else {
@@ -385,7 +395,7 @@ async fn deposit_olp_flow<S: ostium_sdk::TransactionSigner>(
     // Show balance after
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
     let balance_after = client.get_olp_balance().await?;
-    let shares_after = balance_after.shares.to_string().parse::<f64>().unwrap_or(0.0) / 1e6;
+    let shares_after = balance_after.shares_f64();
 
     println!("\nOLP Position AFTER deposit:");
     println!("  Shares: {:.6} (+{:.6})", shares_after, shares_after - shares_before);
@@ -401,7 +411,7 @@ async fn withdraw_olp_flow<S: ostium_sdk::TransactionSigner>(
     println!("\n=== Initialize OLP Withdrawal Request ===");
 
     // Check if vault is configured
-    if client.config().vault.is_none() {
+    if !client.has_vault() {
         println!("OLP Vault is not configured for this network.");
         return Ok(());
     }
@@ -443,7 +453,6 @@ async fn withdraw_olp_flow<S: ostium_sdk::TransactionSigner>(
 
     let futures: Vec<_> = (start_epoch..=current + 1)
         .map(|epoch| {
-            let client = client;
             async move { (epoch, client.get_pending_withdrawal(epoch).await) }
         })
         .collect();
@@ -454,7 +463,7 @@ async fn withdraw_olp_flow<S: ostium_sdk::TransactionSigner>(
     let mut found_pending = false;
     for (epoch, result) in &results {
         if let Ok(pending) = result {
-            let pending_f64: f64 = pending.to_string().parse().unwrap_or(0.0) / 1e6;
+            let pending_f64 = unscale_from_decimals(*pending, balance.share_decimals);
             if pending_f64 > 0.0 {
                 println!("  Epoch {}: {:.6} OLP shares pending", epoch, pending_f64);
                 found_pending = true;
@@ -490,8 +499,13 @@ async fn withdraw_olp_flow<S: ostium_sdk::TransactionSigner>(
         return Ok(());
     }
 
-    // Convert to raw shares (6 decimals)
-    let shares_raw = alloy::primitives::U256::from((shares_to_withdraw * 1e6) as u128);
+    // Convert to raw shares in the OLP token's decimals
+    let shares_raw = scale_to_decimals(shares_to_withdraw, balance.share_decimals);
+
+    match client.quote_withdrawal(shares_raw).await {
+        Ok(quote) => println!("Estimated payout: ${:.2} USDC (after fees)", quote),
+        Err(e) => println!("Could not quote withdrawal: {}", e),
+    }
 
     println!("\nInitiating withdrawal request for {:.6} OLP...", shares_to_withdraw);
     let tx_hash = client.request_olp_withdrawal(shares_raw).await?;
@@ -510,7 +524,6 @@ async fn withdraw_olp_flow<S: ostium_sdk::TransactionSigner>(
 
     let futures: Vec<_> = (start_epoch..=current + 1)
         .map(|epoch| {
-            let client = client;
             async move { (epoch, client.get_pending_withdrawal(epoch).await) }
         })
         .collect();
@@ -521,7 +534,7 @@ async fn withdraw_olp_flow<S: ostium_sdk::TransactionSigner>(
     let mut found_any = false;
     for (epoch, result) in results {
         if let Ok(pending) = result {
-            let pending_f64: f64 = pending.to_string().parse().unwrap_or(0.0) / 1e6;
+            let pending_f64 = unscale_from_decimals(pending, balance.share_decimals);
             if pending_f64 > 0.0 {
                 println!("  Epoch {}: {:.6} OLP shares pending", epoch, pending_f64);
                 found_any = true;
@@ -548,7 +561,7 @@ async fn auto_withdraw_flow<S: ostium_sdk::TransactionSigner>(
     println!("\n=== Auto Withdraw (Approve OLP) ===");
 
     // Check if vault and auto-withdraw are configured
-    if client.config().vault.is_none() {
+    if !client.has_vault() {
         println!("OLP Vault is not configured for this network.");
         return Ok(());
     }
@@ -580,7 +593,7 @@ async fn auto_withdraw_flow<S: ostium_sdk::TransactionSigner>(
     };
 
     let shares_f64 = balance.shares_f64();
-    let allowance_f64: f64 = current_allowance.to_string().parse().unwrap_or(0.0) / 1e6;
+    let allowance_f64: f64 = unscale_from_decimals(current_allowance, balance.share_decimals);
 
     println!("\n--- Current OLP Position ---");
     println!("  Shares: {:.6} OLP", shares_f64);
@@ -610,8 +623,8 @@ async fn auto_withdraw_flow<S: ostium_sdk::TransactionSigner>(
         amount_input.trim().parse().unwrap_or(0.01)
     };
 
-    // Convert to raw shares (6 decimals)
-    let shares_raw = alloy::primitives::U256::from((shares_to_approve * 1e6) as u128);
+    // Convert to raw shares in the OLP token's decimals
+    let shares_raw = scale_to_decimals(shares_to_approve, balance.share_decimals);
 
     println!("\nApproving {:.6} OLP for auto-withdraw...", shares_to_approve);
     let tx_hash = client.approve_auto_withdraw(shares_raw).await?;
@@ -628,7 +641,7 @@ async fn auto_withdraw_flow<S: ostium_sdk::TransactionSigner>(
     // Show updated allowance
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
     let new_allowance = client.get_auto_withdraw_allowance().await?;
-    let new_allowance_f64: f64 = new_allowance.to_string().parse().unwrap_or(0.0) / 1e6;
+    let new_allowance_f64 = unscale_from_decimals(new_allowance, balance.share_decimals);
     println!("\n--- Updated Auto-Withdraw Allowance ---");
     println!("  Allowance: {:.6} OLP", new_allowance_f64);
 
@@ -0,0 +1,111 @@
+//! Blocking wrapper around [`OstiumClient`] for synchronous callers
+//!
+//! Enabled with the `blocking` feature. [`BlockingOstiumClient`] owns a
+//! single-threaded tokio runtime and drives the async client on it.
+//!
+//! # Caveat
+//!
+//! Do not use the blocking client from inside an async runtime: tokio panics
+//! when `block_on` is called from a thread that is already driving a runtime.
+//! Async callers should use [`OstiumClient`] directly.
+
+use crate::client::OstiumClient;
+use crate::config::NetworkConfig;
+use crate::signer::TransactionSigner;
+use crate::types::{BuilderFeeParams, CloseTradeParams, PlaceOrderParams, Position, VaultPosition};
+use alloy::primitives::{Address, TxHash, U256};
+use alloy::rpc::types::TransactionReceipt;
+use eyre::{Context, Result};
+use std::future::Future;
+use tokio::runtime::Runtime;
+
+/// Synchronous Ostium client backed by an internally-managed runtime
+pub struct BlockingOstiumClient<S: TransactionSigner> {
+    inner: OstiumClient<S>,
+    runtime: Runtime,
+}
+
+impl<S: TransactionSigner> BlockingOstiumClient<S> {
+    /// Create a blocking client from an already-constructed signer
+    pub fn new(signer: S, config: NetworkConfig) -> Result<Self> {
+        Self::connect(async { Ok(signer) }, config)
+    }
+
+    /// Create a blocking client, building the signer on the client's runtime
+    ///
+    /// Useful for signers with async constructors, e.g.
+    /// `BlockingOstiumClient::connect(FordefiSigner::discover(&jwt, &pem, &rpc_url), config)`.
+    pub fn connect<F>(signer: F, config: NetworkConfig) -> Result<Self>
+    where
+        F: Future<Output = Result<S>>,
+    {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to create tokio runtime")?;
+
+        let inner = runtime.block_on(async {
+            let signer = signer.await?;
+            OstiumClient::new(signer, config).await
+        })?;
+
+        Ok(Self { inner, runtime })
+    }
+
+    /// Get the underlying async client
+    pub fn inner(&self) -> &OstiumClient<S> {
+        &self.inner
+    }
+
+    /// Run any future to completion on the client's runtime
+    ///
+    /// Covers async client methods that have no blocking wrapper, e.g.
+    /// `client.block_on(client.inner().get_vault_epoch())`.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Get the signer's address
+    pub fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    /// Get USDC balance
+    pub fn get_usdc_balance(&self) -> Result<f64> {
+        self.block_on(self.inner.get_usdc_balance())
+    }
+
+    /// Get ETH balance (for gas)
+    pub fn get_eth_balance(&self) -> Result<U256> {
+        self.block_on(self.inner.get_eth_balance())
+    }
+
+    /// Get OLP vault position
+    pub fn get_olp_balance(&self) -> Result<VaultPosition> {
+        self.block_on(self.inner.get_olp_balance())
+    }
+
+    /// Get open positions for an address (defaults to the signer)
+    pub fn get_positions(&self, trader: Option<Address>) -> Result<Vec<Position>> {
+        self.block_on(self.inner.get_positions(trader))
+    }
+
+    /// Place a new order
+    pub fn place_order(
+        &self,
+        params: PlaceOrderParams,
+        builder_fee: Option<BuilderFeeParams>,
+    ) -> Result<TxHash> {
+        self.block_on(self.inner.place_order(params, builder_fee))
+    }
+
+    /// Close a trade at market price
+    pub fn close_trade(&self, params: CloseTradeParams) -> Result<TxHash> {
+        self.block_on(self.inner.close_trade(params))
+    }
+
+    /// Wait for a transaction receipt
+    pub fn wait_for_receipt(&self, tx_hash: TxHash) -> Result<TransactionReceipt> {
+        self.block_on(self.inner.wait_for_receipt(tx_hash))
+    }
+}
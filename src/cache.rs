@@ -0,0 +1,283 @@
+//! Small in-memory caches used by `OstiumClient`
+//!
+//! Caches are filled through `&self`, so a client shared across tasks (e.g.
+//! behind an `Arc`) populates them lazily without `&mut` access.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Keyed cache whose entries expire after a fixed time-to-live
+#[derive(Debug)]
+pub(crate) struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: RwLock<HashMap<K, (Instant, V)>>,
+    /// Bumped per key by `invalidate`, and for every key by `clear`
+    generations: Mutex<Generations<K>>,
+    /// Per-key locks held while a missing entry is fetched
+    fills: Mutex<HashMap<K, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    /// Create an empty cache with the given TTL
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+            generations: Mutex::new(Generations::default()),
+            fills: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get a cached value, or fetch and insert it if missing or expired
+    ///
+    /// Concurrent callers missing the same key wait for a single fetch instead
+    /// of each running their own. A failed fetch caches nothing, and the next
+    /// waiter retries it. The per-key lock is dropped once its last waiter is
+    /// done. A fetch overlapping an `invalidate` or `clear` of its key still
+    /// returns its value, but doesn't cache it.
+    pub(crate) async fn get_or_try_insert_with<F, Fut, E>(&self, key: K, fetch: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+
+        let fill = {
+            let mut fills = self.fills.lock().unwrap_or_else(|e| e.into_inner());
+            FillSlot {
+                fills: &self.fills,
+                key: key.clone(),
+                lock: fills.entry(key.clone()).or_default().clone(),
+            }
+        };
+        let _guard = fill.lock.lock().await;
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+
+        let generation = self.generation(&key);
+        let value = fetch().await?;
+        self.insert_if_current(key, value.clone(), generation);
+        Ok(value)
+    }
+
+    /// Get a cached value if present and not expired
+    pub(crate) fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Insert or replace a value
+    pub(crate) fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// Current generation of a key, to pass to [`Self::insert_if_current`]
+    /// once a fetch started now completes
+    pub(crate) fn generation(&self, key: &K) -> Generation {
+        let generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+        generations.get(key)
+    }
+
+    /// Insert a value fetched at `generation`, unless the key was invalidated
+    /// or the cache cleared since
+    ///
+    /// Returns whether the value was inserted.
+    pub(crate) fn insert_if_current(&self, key: K, value: V, generation: Generation) -> bool {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+        if generations.get(&key) != generation {
+            return false;
+        }
+        entries.insert(key, (Instant::now(), value));
+        true
+    }
+
+    /// Remove a single entry
+    pub(crate) fn invalidate(&self, key: &K) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let mut generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+        *generations.keys.entry(key.clone()).or_default() += 1;
+        entries.remove(key);
+    }
+
+    /// Remove all entries
+    pub(crate) fn clear(&self) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let mut generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+        generations.all += 1;
+        entries.clear();
+    }
+}
+
+/// Snapshot of a key's invalidation count, taken before a fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Generation {
+    all: u64,
+    key: u64,
+}
+
+/// Invalidation counters for a [`TtlCache`]
+#[derive(Debug)]
+struct Generations<K> {
+    /// Bumped by `clear`
+    all: u64,
+    /// Bumped by `invalidate`
+    keys: HashMap<K, u64>,
+}
+
+impl<K> Default for Generations<K> {
+    fn default() -> Self {
+        Self {
+            all: 0,
+            keys: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> Generations<K> {
+    fn get(&self, key: &K) -> Generation {
+        Generation {
+            all: self.all,
+            key: self.keys.get(key).copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// A caller's handle on a per-key fill lock
+///
+/// Dropping the last handle removes the lock from the map, including when the
+/// fetching future is cancelled.
+struct FillSlot<'a, K: Eq + Hash> {
+    fills: &'a Mutex<HashMap<K, Arc<tokio::sync::Mutex<()>>>>,
+    key: K,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl<K: Eq + Hash> Drop for FillSlot<'_, K> {
+    fn drop(&mut self) {
+        // Handles are only cloned under the map lock, so the count can't grow
+        // here; the map holds one reference and this slot the other
+        let mut fills = self.fills.lock().unwrap_or_else(|e| e.into_inner());
+        if Arc::strong_count(&self.lock) == 2
+            && fills
+                .get(&self.key)
+                .is_some_and(|lock| Arc::ptr_eq(lock, &self.lock))
+        {
+            fills.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_ttl_cache_expiry_and_invalidation() {
+        let cache = TtlCache::new(Duration::from_millis(50));
+        cache.insert(1u8, "a");
+        assert_eq!(cache.get(&1), Some("a"));
+
+        cache.invalidate(&1);
+        assert_eq!(cache.get(&1), None);
+
+        cache.insert(2u8, "b");
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(cache.get(&2), None);
+    }
+
+    #[test]
+    fn test_unbounded_ttl_holds_until_cleared() {
+        // Protocol parameters are cached this way until an explicit refresh
+        let cache = TtlCache::new(Duration::MAX);
+        cache.insert((), 42u8);
+        assert_eq!(cache.get(&()), Some(42));
+        cache.clear();
+        assert_eq!(cache.get(&()), None);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_fetch_once() {
+        let cache = Arc::new(TtlCache::new(Duration::from_secs(60)));
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..32)
+            .map(|_| {
+                let (cache, fetches) = (cache.clone(), fetches.clone());
+                tokio::spawn(async move {
+                    cache
+                        .get_or_try_insert_with(1u8, || async {
+                            fetches.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(20)).await;
+                            Ok::<_, String>(42u64)
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), Ok(42));
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Failed fetches are not cached
+        let failed = cache
+            .get_or_try_insert_with(2u8, || async { Err::<u64, _>("down".to_string()) })
+            .await;
+        assert!(failed.is_err());
+        let retried = cache
+            .get_or_try_insert_with(2u8, || async { Ok::<_, String>(7) })
+            .await;
+        assert_eq!(retried, Ok(7));
+
+        // Fill locks don't outlive their fetches
+        assert!(cache.fills.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_during_fetch_skips_insert() {
+        let cache = Arc::new(TtlCache::new(Duration::from_secs(60)));
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let fill = tokio::spawn({
+            let cache = cache.clone();
+            async move {
+                cache
+                    .get_or_try_insert_with(1u8, || async {
+                        started_tx.send(()).unwrap();
+                        release_rx.await.unwrap();
+                        Ok::<_, String>("stale")
+                    })
+                    .await
+            }
+        });
+
+        // A write lands while the slow fetch is still reading
+        started_rx.await.unwrap();
+        cache.invalidate(&1);
+        release_tx.send(()).unwrap();
+
+        // The caller still gets its value, but it isn't cached
+        assert_eq!(fill.await.unwrap(), Ok("stale"));
+        assert_eq!(cache.get(&1), None);
+
+        // Clearing the cache also discards in-flight fetches
+        let generation = cache.generation(&2);
+        cache.clear();
+        assert!(!cache.insert_if_current(2, "stale", generation));
+        assert!(cache.insert_if_current(2, "fresh", cache.generation(&2)));
+        assert_eq!(cache.get(&2), Some("fresh"));
+    }
+}
@@ -1,68 +1,376 @@
 //! OstiumClient - main entry point for the SDK
 
+use crate::cache::TtlCache;
 use crate::config::NetworkConfig;
-use crate::constants::scale_usdc;
-use crate::contracts::{IERC20, IOstiumVault, ITrading, ITradingStorage};
-use crate::signer::{TransactionSigner, TxRequest};
+use crate::constants::{
+    unscale_from_decimals, AUTO_SLIPPAGE_SAMPLES, AUTO_SLIPPAGE_SAMPLE_INTERVAL_MS,
+    LEVERAGE_DECIMALS, PRICE_DECIMALS, SWAP_FEE_TIER, USDC_DECIMALS,
+};
+use crate::contracts::{IOstiumVault, ISwapRouter, ITrading, ITradingStorage, TradeInfo, IERC20};
+use crate::error::{classify_send_error, retry_safe, OstiumError};
+use crate::price::{
+    get_all_prices_with_http_config, get_price_data_with_http_config, PriceAlert, PriceData,
+};
+use crate::signer::{PollConfig, SignerKind, TransactionSigner, TxRequest};
+use crate::subgraph::{SubgraphClient, SubgraphOrder, SubgraphPair};
 use crate::types::{
-    BuilderFeeParams, CloseTradeParams, DepositParams, PlaceOrderParams, Position,
-    VaultEpoch, VaultPosition,
+    diff_positions, parse_scaled, realized_pnl, AccountValue, ApprovalMode, BuilderFeeParams,
+    CloseQuote, CloseResult, CloseTradeParams, CollateralPreview, ConnectivityReport, DecodedCall,
+    DepositParams, EndpointStatus, LifecycleEvent, LifecycleEventKind, OpenOrder, OstiumEvent,
+    PairInfo, PlaceOrderParams, PnlBreakdown, Position, PositionDiff, PositionExposure,
+    PositionScanResult, PositionSnapshot, PositionSource, PriceRequest, ProtocolLimits,
+    ReceiptSummary, RedeemParams, SlippageMode, SlippageRecommendation, TokenMetadata, TradeEvent,
+    TradeFees, TradeLifecycle, TradeTicket, VaultEpoch, VaultPosition, VaultUtilization,
 };
+use alloy::dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy::json_abi::Param;
 use alloy::network::{Ethereum, TransactionBuilder};
-use alloy::primitives::{Address, Bytes, TxHash, U256};
-use alloy::providers::{Provider, ProviderBuilder, RootProvider};
-use alloy::rpc::types::TransactionReceipt;
-use alloy::sol_types::SolCall;
+use alloy::primitives::aliases::{U160, U24};
+use alloy::primitives::utils::format_units;
+use alloy::primitives::{Address, Bytes, TxHash, B256, U256};
+use alloy::providers::{Provider, ProviderBuilder, RootProvider, WsConnect};
+use alloy::rpc::types::{Filter, Log, TransactionReceipt};
+use alloy::sol_types::{SolCall, SolEvent};
 use alloy::transports::http::reqwest::Url;
-use eyre::{Context, Result};
+use eyre::{Context, Report, Result};
+use futures::{Stream, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
 
 /// Type alias for read-only provider
 type ReadProvider = Arc<RootProvider<Ethereum>>;
 
+/// Number of pairs scanned when enumerating trades and orders
+/// Could be expanded based on pairsCount() if needed
+const MAX_SCANNED_PAIRS: u16 = 50;
+
+/// Default limit on concurrent RPC reads in multi-call scans
+pub const DEFAULT_READ_CONCURRENCY: usize = 10;
+
+/// Default safety margin added on top of `eth_estimateGas` for writes
+pub const DEFAULT_GAS_BUFFER_PERCENT: u64 = 20;
+
+/// How often `withdraw_when_open` re-reads the vault epoch
+const WITHDRAWAL_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long symbol to pair index lookups are cached, so new listings show up
+const PAIR_SYMBOL_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// How long a trader's positions bypass the cache after a trade write, since
+/// the keeper fills market orders in a later transaction
+const PENDING_FILL_WINDOW: Duration = Duration::from_secs(60);
+
 /// Main client for interacting with Ostium protocol
 pub struct OstiumClient<S: TransactionSigner> {
     signer: S,
     config: NetworkConfig,
     provider: ReadProvider,
+    /// Subgraph client (if a subgraph URL is configured)
+    subgraph: Option<SubgraphClient>,
+    /// Optional `get_positions` cache keyed by trader address
+    positions_cache: Option<TtlCache<Address, Vec<Position>>>,
+    /// Traders with a recent trade write whose fill may not have landed yet
+    pending_fills: TtlCache<Address, ()>,
+    /// Pair indices keyed by uppercase `(from, to)` symbols
+    pair_indices: TtlCache<(String, String), u16>,
+    /// Percentage added to estimated gas when setting a write's gas limit
+    gas_buffer_percent: u64,
+    /// Pair count and trades-per-pair limit, read on first use until refreshed
+    protocol_limits: TtlCache<(), ProtocolLimits>,
+    /// Approve zero before changing a non-zero allowance
+    reset_allowance_before_change: bool,
+    /// Maximum concurrent RPC reads in multi-call scans like `get_positions`
+    read_concurrency: usize,
+    /// USDC name/symbol/decimals, read on first use until refreshed
+    usdc_metadata: TtlCache<(), TokenMetadata>,
+    /// OLP share token name/symbol/decimals, read on first use until refreshed
+    olp_metadata: TtlCache<(), TokenMetadata>,
+    /// Polling limits passed to the signer when waiting for receipts
+    poll_config: Option<PollConfig>,
+    /// Fixed gas limit for writes, replacing estimation
+    gas_limit: Option<u64>,
+    /// Slippage for orders and closes that don't set their own
+    default_slippage: Option<f64>,
+    /// Builder fee attached to orders placed without one
+    default_builder_fee: Option<BuilderFeeParams>,
 }
 
 impl<S: TransactionSigner> OstiumClient<S> {
     /// Create a new OstiumClient
+    ///
+    /// `ws://` and `wss://` RPC URLs connect over WebSocket, which enables
+    /// `eth_subscribe`-based event streams; any other URL uses HTTP.
     pub async fn new(signer: S, config: NetworkConfig) -> Result<Self> {
-        let url: Url = config.rpc_url.parse().context("Invalid RPC URL")?;
         // Read-only provider without fillers (we only do eth_call operations)
-        let provider = ProviderBuilder::new()
-            .disable_recommended_fillers()
-            .network::<Ethereum>()
-            .connect_http(url);
+        let provider = if config.is_websocket() {
+            ProviderBuilder::new()
+                .disable_recommended_fillers()
+                .network::<Ethereum>()
+                .connect_ws(WsConnect::new(config.rpc_url.clone()))
+                .await
+                .context("Failed to connect to WebSocket RPC")?
+        } else {
+            let url: Url = config.rpc_url.parse().context("Invalid RPC URL")?;
+            ProviderBuilder::new()
+                .disable_recommended_fillers()
+                .network::<Ethereum>()
+                .connect_http(url)
+        };
+
+        Self::with_provider(signer, config, provider)
+    }
+
+    /// Build a client around an already-connected read provider
+    fn with_provider(
+        signer: S,
+        config: NetworkConfig,
+        provider: RootProvider<Ethereum>,
+    ) -> Result<Self> {
+        let subgraph = config
+            .subgraph_url
+            .as_ref()
+            .map(|url| SubgraphClient::new(url, &config.http))
+            .transpose()?;
 
         Ok(Self {
             signer,
             config,
             provider: Arc::new(provider),
+            subgraph,
+            positions_cache: None,
+            pending_fills: TtlCache::new(PENDING_FILL_WINDOW),
+            pair_indices: TtlCache::new(PAIR_SYMBOL_CACHE_TTL),
+            gas_buffer_percent: DEFAULT_GAS_BUFFER_PERCENT,
+            protocol_limits: TtlCache::new(Duration::MAX),
+            reset_allowance_before_change: false,
+            read_concurrency: DEFAULT_READ_CONCURRENCY,
+            usdc_metadata: TtlCache::new(Duration::MAX),
+            olp_metadata: TtlCache::new(Duration::MAX),
+            poll_config: None,
+            gas_limit: None,
+            default_slippage: None,
+            default_builder_fee: None,
         })
     }
 
+    /// Enable caching of `get_positions` results for the given TTL
+    ///
+    /// Cached entries for the signer's address are invalidated automatically
+    /// whenever this client submits a trade write (`place_order`, `close_trade`).
+    /// Market orders fill in a later keeper transaction, so for a short window
+    /// after a write that trader's positions are read fresh and not cached.
+    pub fn with_positions_cache(mut self, ttl: Duration) -> Self {
+        self.positions_cache = Some(TtlCache::new(ttl));
+        self
+    }
+
+    /// Set the safety margin applied to estimated gas for writes
+    ///
+    /// Defaults to [`DEFAULT_GAS_BUFFER_PERCENT`].
+    pub fn with_gas_buffer(mut self, buffer_percent: u64) -> Self {
+        self.gas_buffer_percent = buffer_percent;
+        self
+    }
+
+    /// Use a fixed gas limit for every write instead of estimating
+    ///
+    /// Applies to all transactions this client sends (orders, closes,
+    /// approvals, vault operations). Unset by default, in which case each
+    /// write's limit is estimated and padded by the gas buffer.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Set the slippage percentage used when an order or close leaves
+    /// `slippage` unset
+    ///
+    /// Per-order `with_slippage` values still take precedence. Without a
+    /// client default, unset slippage falls back to
+    /// [`DEFAULT_SLIPPAGE`](crate::constants::DEFAULT_SLIPPAGE).
+    pub fn with_default_slippage(mut self, slippage_percent: f64) -> Self {
+        self.default_slippage = Some(slippage_percent);
+        self
+    }
+
+    /// Attach a builder fee to every order placed with `builder_fee: None`
+    ///
+    /// Pass `Some(BuilderFeeParams::none())` to an individual order to open it
+    /// without the default fee.
+    pub fn with_default_builder_fee(mut self, builder_fee: BuilderFeeParams) -> Self {
+        self.default_builder_fee = Some(builder_fee);
+        self
+    }
+
+    /// Set the maximum number of concurrent RPC reads in multi-call scans
+    ///
+    /// Defaults to [`DEFAULT_READ_CONCURRENCY`]. Lower it for rate-limited
+    /// endpoints such as the public Arbitrum RPC.
+    pub fn with_read_concurrency(mut self, read_concurrency: usize) -> Self {
+        self.read_concurrency = read_concurrency;
+        self
+    }
+
+    /// Set the polling limits used when this client waits for receipts
+    ///
+    /// Overrides the signer's own limits for waits the client orchestrates
+    /// (`wait_for_receipt`, allowance resets). Unset by default, in which
+    /// case the signer's configuration applies.
+    pub fn with_poll_config(mut self, poll_config: PollConfig) -> Self {
+        self.poll_config = Some(poll_config);
+        self
+    }
+
+    /// Reset non-zero allowances to zero before changing them
+    ///
+    /// Arbitrum USDC doesn't need this, but some ERC-20s reject changing one
+    /// non-zero allowance to another. Off by default, since it costs an extra
+    /// approval transaction.
+    pub fn with_allowance_reset(mut self, reset_allowance_before_change: bool) -> Self {
+        self.reset_allowance_before_change = reset_allowance_before_change;
+        self
+    }
+
+    /// Clear all cached `get_positions` results
+    pub fn invalidate_positions_cache(&self) {
+        if let Some(cache) = &self.positions_cache {
+            cache.clear();
+        }
+    }
+
+    /// Clear every cache, so the next reads go to the chain and subgraph
+    ///
+    /// The client caches:
+    /// - `get_positions` results, if enabled via [`Self::with_positions_cache`]
+    ///   (its TTL)
+    /// - symbol to pair index lookups from [`Self::resolve_pair`] (1 hour)
+    /// - the pair count and trades-per-pair limit (until refreshed)
+    /// - USDC and OLP token name, symbol and decimals (until refreshed)
+    ///
+    /// Prices are never cached. Use after a known state change such as a
+    /// contract upgrade, or to force fresh data.
+    pub fn refresh(&self) {
+        self.refresh_positions();
+        self.refresh_protocol_params();
+    }
+
+    /// Clear cached `get_positions` results for every trader
+    pub fn refresh_positions(&self) {
+        self.invalidate_positions_cache();
+    }
+
+    /// Clear cached protocol parameters: limits, pair indices and token metadata
+    pub fn refresh_protocol_params(&self) {
+        self.protocol_limits.clear();
+        self.pair_indices.clear();
+        self.usdc_metadata.clear();
+        self.olp_metadata.clear();
+    }
+
+    /// Drop the cached positions for the signer after a trade write
+    fn invalidate_own_positions(&self) {
+        self.invalidate_cached_positions(self.address());
+    }
+
+    /// Drop the cached positions for a trader after a write on their behalf,
+    /// and stop caching them until the keeper fill has had time to land
+    fn invalidate_cached_positions(&self, trader: Address) {
+        if let Some(cache) = &self.positions_cache {
+            self.pending_fills.insert(trader, ());
+            cache.invalidate(&trader);
+        }
+    }
+
+    /// Drop the cached positions for a trader once a pending fill has landed,
+    /// and resume caching them
+    fn settle_cached_positions(&self, trader: Address) {
+        if let Some(cache) = &self.positions_cache {
+            self.pending_fills.invalidate(&trader);
+            cache.invalidate(&trader);
+        }
+    }
+
+    /// Positions cache to use for a trader, unless a fill may be pending
+    fn positions_cache_for(&self, trader: Address) -> Option<&TtlCache<Address, Vec<Position>>> {
+        self.positions_cache
+            .as_ref()
+            .filter(|_| self.pending_fills.get(&trader).is_none())
+    }
+
     /// Get the signer's address
     pub fn address(&self) -> Address {
         self.signer.address()
     }
 
+    /// Get the signing backend of the client's signer
+    pub fn signer_kind(&self) -> SignerKind {
+        self.signer.signer_kind()
+    }
+
     /// Get the network configuration
     pub fn config(&self) -> &NetworkConfig {
         &self.config
     }
 
+    /// Get the subgraph client
+    pub fn subgraph(&self) -> Result<&SubgraphClient> {
+        self.subgraph
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("Subgraph URL not configured"))
+    }
+
+    /// Get the OLP vault address, or an error if none is configured
+    pub fn vault(&self) -> Result<Address> {
+        self.config
+            .vault
+            .ok_or_else(|| eyre::eyre!("Vault address not configured"))
+    }
+
+    /// Whether an OLP vault is configured, so vault methods can be used
+    pub fn has_vault(&self) -> bool {
+        self.config.vault.is_some()
+    }
+
     // ========== Token Operations ==========
 
     /// Get USDC balance
     pub async fn get_usdc_balance(&self) -> Result<f64> {
-        let balance = self.get_token_balance(self.config.usdc).await?;
-        Ok(crate::constants::unscale_from_decimals(
-            balance,
-            crate::constants::USDC_DECIMALS,
-        ))
+        let (balance, decimals) = tokio::try_join!(
+            self.get_token_balance(self.config.usdc),
+            self.get_usdc_decimals()
+        )?;
+        Ok(crate::constants::unscale_from_decimals(balance, decimals))
+    }
+
+    /// Get the collateral token's name, symbol, and decimals
+    ///
+    /// Read from the USDC contract on first use and cached until [`Self::refresh`].
+    pub async fn token_metadata(&self) -> Result<TokenMetadata> {
+        self.usdc_metadata
+            .get_or_try_insert_with((), || async {
+                let usdc = self.config.usdc;
+                let (name, symbol, decimals) = tokio::try_join!(
+                    self.view_call(usdc, IERC20::nameCall {}),
+                    self.view_call(usdc, IERC20::symbolCall {}),
+                    self.view_call(usdc, IERC20::decimalsCall {}),
+                )
+                .context("Failed to get USDC metadata")?;
+
+                Ok(TokenMetadata {
+                    name,
+                    symbol,
+                    decimals,
+                })
+            })
+            .await
+    }
+
+    /// Get the collateral token's decimals (cached)
+    pub async fn get_usdc_decimals(&self) -> Result<u8> {
+        Ok(self.token_metadata().await?.decimals)
     }
 
     /// Get token balance
@@ -99,14 +407,32 @@ impl<S: TransactionSigner> OstiumClient<S> {
         let data = Bytes::from(call.abi_encode());
 
         let tx = TxRequest::new(token, data);
-        self.signer
-            .sign_and_send(tx)
-            .await
-            .context("Failed to approve token")
+        self.send(tx).await.context("Failed to approve token")
     }
 
     /// Check and ensure USDC allowance
     async fn ensure_usdc_allowance(&self, spender: Address, amount: U256) -> Result<()> {
+        self.ensure_allowance(self.config.usdc, spender, amount)
+            .await
+    }
+
+    /// Check and ensure the signer's allowance of `token` to `spender`
+    async fn ensure_allowance(&self, token: Address, spender: Address, amount: U256) -> Result<()> {
+        // Approve only the exact amount needed
+        self.raise_allowance(token, spender, amount, amount).await?;
+        Ok(())
+    }
+
+    /// Raise the allowance to `target` if it is below `required`
+    ///
+    /// Returns the hashes of the approvals sent (none if already sufficient).
+    async fn raise_allowance(
+        &self,
+        token: Address,
+        spender: Address,
+        required: U256,
+        target: U256,
+    ) -> Result<Vec<TxHash>> {
         let call = IERC20::allowanceCall {
             owner: self.address(),
             spender,
@@ -117,7 +443,7 @@ impl<S: TransactionSigner> OstiumClient<S> {
             .provider
             .call(
                 alloy::rpc::types::TransactionRequest::default()
-                    .with_to(self.config.usdc)
+                    .with_to(token)
                     .with_input(data),
             )
             .await
@@ -126,13 +452,38 @@ impl<S: TransactionSigner> OstiumClient<S> {
         let decoded = IERC20::allowanceCall::abi_decode_returns(&result)
             .context("Failed to decode allowance")?;
 
-        if decoded < amount {
-            // Approve only the exact amount needed
-            self.approve_token(self.config.usdc, spender, amount)
-                .await?;
+        if decoded >= required {
+            return Ok(Vec::new());
         }
+        self.set_allowance(token, spender, decoded, target).await
+    }
 
-        Ok(())
+    /// Change an allowance from `current` to `target`
+    ///
+    /// With `reset_allowance_before_change` enabled, a non-zero allowance is
+    /// first reset to zero (waiting for that approval to be mined), for tokens
+    /// that reject changing one non-zero allowance to another.
+    async fn set_allowance(
+        &self,
+        token: Address,
+        spender: Address,
+        current: U256,
+        target: U256,
+    ) -> Result<Vec<TxHash>> {
+        let steps = approval_steps(current, target, self.reset_allowance_before_change);
+        let last = steps.len().saturating_sub(1);
+        let mut tx_hashes = Vec::with_capacity(steps.len());
+
+        for (i, amount) in steps.into_iter().enumerate() {
+            let tx_hash = self.approve_token(token, spender, amount).await?;
+            if i < last {
+                let receipt = self.wait_for_receipt(tx_hash).await?;
+                eyre::ensure!(receipt.status(), "Allowance reset transaction reverted");
+            }
+            tx_hashes.push(tx_hash);
+        }
+
+        Ok(tx_hashes)
     }
 
     // ========== Trading Operations ==========
@@ -147,195 +498,1595 @@ impl<S: TransactionSigner> OstiumClient<S> {
     /// # Returns
     ///
     /// Transaction hash of the submitted order
+    ///
+    /// # Errors
+    ///
+    /// Failures are classified with [`crate::OstiumError`]. Only errors for which
+    /// [`crate::error::is_retry_safe`] returns true (validation and allowance
+    /// failures before the order is broadcast) should be retried automatically;
+    /// a retry-unsafe failure may already have submitted the order.
     pub async fn place_order(
         &self,
         params: PlaceOrderParams,
         builder_fee: Option<BuilderFeeParams>,
     ) -> Result<TxHash> {
-        // Validate parameters
-        params.validate()?;
-
-        // Ensure USDC allowance to TradingStorage
-        let collateral = scale_usdc(params.collateral);
-        self.ensure_usdc_allowance(self.config.trading_storage, collateral)
-            .await?;
-
-        // Build trade struct
-        let trade_index = params.trade_index.unwrap_or(0);
-        let trade = params.to_trade(self.address(), trade_index);
-        let builder_fee = builder_fee.unwrap_or_default().to_builder_fee();
-        let slippage = params.scaled_slippage();
+        let params = self
+            .resolve_order_pair(params)
+            .await
+            .map_err(|e| retry_safe(e, "Failed to resolve order pair"))?;
+        let params = self
+            .resolve_auto_slippage(self.with_order_defaults(params))
+            .await;
 
-        // Encode call
-        let call = ITrading::openTradeCall {
-            t: trade,
-            bf: builder_fee,
-            orderType: params.order_type.into(),
-            slippageP: slippage,
-        };
-        let data = Bytes::from(call.abi_encode());
+        self.validate_order(&params)
+            .await
+            .map_err(|e| retry_safe(e, "Invalid order parameters"))?;
+
+        let trader = params.trader.unwrap_or_else(|| self.address());
+        let collateral = params.scaled_collateral();
+        if trader == self.address() {
+            // Ensure USDC allowance to TradingStorage
+            self.ensure_usdc_allowance(self.config.trading_storage, collateral)
+                .await
+                .map_err(|e| retry_safe(e, "Failed to ensure USDC allowance"))?;
+        } else {
+            self.check_delegation(trader, collateral)
+                .await
+                .map_err(|e| retry_safe(e, "Delegated order rejected"))?;
+        }
 
         // Send transaction
-        let tx = TxRequest::new(self.config.trading, data);
-        self.signer
-            .sign_and_send(tx)
+        let tx = self.open_trade_tx(&params, builder_fee);
+        let result = self
+            .send(tx)
             .await
-            .context("Failed to place order")
+            .map_err(|e| classify_send_error(e, "Failed to place order"));
+        self.invalidate_cached_positions(trader);
+        result
     }
 
-    /// Close a trade at market price
+    /// Place an order, returning a handle to await it
     ///
-    /// # Arguments
+    /// See [`Self::place_order`].
+    pub async fn place_order_pending(
+        &self,
+        params: PlaceOrderParams,
+        builder_fee: Option<BuilderFeeParams>,
+    ) -> Result<PendingTransaction<'_, S>> {
+        let hash = self.place_order(params, builder_fee).await?;
+        Ok(self.pending_transaction(hash))
+    }
+
+    /// Open several orders (legs) for the signer with one combined allowance
     ///
-    /// * `params` - Close trade parameters
+    /// Every leg is validated with [`Self::validate_order`], including the
+    /// pair's minimum collateral, before anything is sent. Then the USDC
+    /// allowance to TradingStorage is raised once to cover the total
+    /// collateral, and the legs are submitted in order. The signer assigns nonces as each leg is
+    /// sent, so the legs are independent transactions, not an atomic batch.
     ///
     /// # Returns
     ///
-    /// Transaction hash of the close order
-    pub async fn close_trade(&self, params: CloseTradeParams) -> Result<TxHash> {
-        let call = ITrading::closeTradeMarketCall {
-            pairIndex: params.pair_index,
-            index: params.trade_index,
-            closePercentage: params.scaled_close_percentage(),
-            marketPrice: params.scaled_market_price(),
-            slippageP: params.scaled_slippage(),
-        };
-        let data = Bytes::from(call.abi_encode());
+    /// Transaction hashes of the legs, in the order given
+    ///
+    /// # Errors
+    ///
+    /// If a leg fails to submit, the error names it and lists the legs that
+    /// were already sent; those are not rolled back.
+    pub async fn open_multiple(
+        &self,
+        orders: Vec<PlaceOrderParams>,
+        builder_fee: Option<BuilderFeeParams>,
+    ) -> Result<Vec<TxHash>> {
+        let mut legs = Vec::with_capacity(orders.len());
+        for (leg, params) in orders.into_iter().enumerate() {
+            let params = self
+                .resolve_order_pair(params)
+                .await
+                .map_err(|e| retry_safe(e, &format!("Failed to resolve pair for leg {}", leg)))?;
+            let params = self
+                .resolve_auto_slippage(self.with_order_defaults(params))
+                .await;
+            self.validate_order(&params)
+                .await
+                .map_err(|e| retry_safe(e, &format!("Invalid parameters for leg {}", leg)))?;
+            eyre::ensure!(
+                params.trader.is_none_or(|trader| trader == self.address()),
+                "Leg {} is for another trader; open_multiple only opens the signer's trades",
+                leg
+            );
+            legs.push(params);
+        }
 
-        let tx = TxRequest::new(self.config.trading, data);
-        self.signer
-            .sign_and_send(tx)
+        let total_collateral = legs.iter().fold(U256::ZERO, |total, params| {
+            total + params.scaled_collateral()
+        });
+        self.ensure_usdc_allowance(self.config.trading_storage, total_collateral)
             .await
-            .context("Failed to close trade")
-    }
+            .map_err(|e| retry_safe(e, "Failed to ensure USDC allowance"))?;
+
+        let mut tx_hashes = Vec::with_capacity(legs.len());
+        for (leg, params) in legs.iter().enumerate() {
+            let result = self
+                .send(self.open_trade_tx(params, builder_fee.clone()))
+                .await
+                .map_err(|e| classify_send_error(e, "Failed to place order"));
+            let tx_hash = result.wrap_err_with(|| {
+                format!(
+                    "Leg {} failed after opening legs {:?} ({:?})",
+                    leg,
+                    (0..leg).collect::<Vec<_>>(),
+                    tx_hashes
+                )
+            });
+            self.invalidate_cached_positions(self.address());
+            tx_hashes.push(tx_hash?);
+        }
 
-    // ========== Position Queries (Direct Contract Calls) ==========
+        Ok(tx_hashes)
+    }
 
-    /// Get all open positions for an address directly from TradingStorage contract
+    /// Place a new order funded in another ERC-20 token
     ///
-    /// Iterates through all trading pairs to find open positions.
+    /// Swaps up to `max_input` of `input_token` for exactly the order's
+    /// collateral in USDC through the configured Uniswap V3 router (pool fee
+    /// tier [`SWAP_FEE_TIER`]), waits for the swap to be mined, then places the
+    /// order as [`Self::place_order`] does. To fund with ETH, wrap it to WETH first.
+    ///
+    /// The pair, slippage and [`Self::validate_order`] checks run before the
+    /// swap, so an order that can't pass them never swaps. If the swap
+    /// succeeds but the order still fails (e.g. it reverts), the swapped USDC
+    /// stays in the signer's wallet.
     ///
     /// # Arguments
     ///
-    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    /// * `params` - Order parameters; the collateral is the USDC amount to buy
+    /// * `builder_fee` - Optional builder/referral fee parameters
+    /// * `input_token` - ERC-20 token to sell
+    /// * `max_input` - Maximum amount of `input_token` to sell (raw units)
     ///
     /// # Returns
     ///
-    /// Vector of Position structs representing open trades
-    pub async fn get_positions(&self, trader: Option<Address>) -> Result<Vec<Position>> {
-        let trader = trader.unwrap_or_else(|| self.address());
-        let mut positions = Vec::new();
+    /// Transaction hashes of the swap and of the order
+    pub async fn place_order_with_token(
+        &self,
+        params: PlaceOrderParams,
+        builder_fee: Option<BuilderFeeParams>,
+        input_token: Address,
+        max_input: U256,
+    ) -> Result<(TxHash, TxHash)> {
+        let router = self
+            .config
+            .router
+            .ok_or_else(|| eyre::eyre!("Router address not configured"))?;
+        let params = self
+            .resolve_order_pair(params)
+            .await
+            .map_err(|e| retry_safe(e, "Failed to resolve order pair"))?;
+        let params = self
+            .resolve_auto_slippage(self.with_order_defaults(params))
+            .await;
+        self.validate_order(&params)
+            .await
+            .map_err(|e| retry_safe(e, "Invalid order parameters"))?;
+        eyre::ensure!(
+            params.trader.is_none_or(|trader| trader == self.address()),
+            "Token-funded orders can't be opened for another trader"
+        );
+        eyre::ensure!(
+            input_token != self.config.usdc,
+            "Input token is already USDC, use place_order"
+        );
+
+        self.ensure_allowance(input_token, router, max_input)
+            .await
+            .context("Failed to ensure input token allowance")?;
+
+        let call = ISwapRouter::exactOutputSingleCall {
+            params: ISwapRouter::ExactOutputSingleParams {
+                tokenIn: input_token,
+                tokenOut: self.config.usdc,
+                fee: U24::from(SWAP_FEE_TIER),
+                recipient: self.address(),
+                amountOut: params.scaled_collateral(),
+                amountInMaximum: max_input,
+                sqrtPriceLimitX96: U160::ZERO,
+            },
+        };
+        let swap_hash = self
+            .send(TxRequest::new(router, call.abi_encode()))
+            .await
+            .context("Failed to swap into USDC")?;
+        let receipt = self.wait_for_receipt(swap_hash).await?;
+        eyre::ensure!(receipt.status(), "Swap transaction {} reverted", swap_hash);
 
-        // Query positions for the most common pairs (0-49)
-        // Could be expanded based on pairsCount() if needed
-        let max_pairs: u16 = 50;
-        let max_trades_per_pair: u8 = 3; // Ostium allows up to 3 trades per pair
+        let trade_hash = self.place_order(params, builder_fee).await?;
+        Ok((swap_hash, trade_hash))
+    }
 
-        for pair_index in 0..max_pairs {
-            // Check open trades count for this pair
-            let count = self.get_open_trades_count(trader, pair_index).await?;
-            if count == 0 {
-                continue;
+    /// Place an order and wait until it is filled
+    ///
+    /// Submits the order as [`Self::place_order`] does, waits for the receipt
+    /// (reporting the revert reason if it failed), then polls the pair's
+    /// trade slots until the oracle fills the order and a new position
+    /// appears. Orders the oracle rejects (e.g. market closed) never produce
+    /// a position and end in a timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Order parameters including pair, collateral, leverage, etc.
+    /// * `builder_fee` - Optional builder/referral fee parameters
+    /// * `timeout` - Overall limit for submission, confirmation, and fill
+    ///
+    /// # Returns
+    ///
+    /// The opened position, including its protocol trade ID
+    pub async fn open_and_wait(
+        &self,
+        params: PlaceOrderParams,
+        builder_fee: Option<BuilderFeeParams>,
+        timeout: Duration,
+    ) -> Result<Position> {
+        let params = self.resolve_order_pair(params).await?;
+        let trader = params.trader.unwrap_or_else(|| self.address());
+        let pair_index = params.pair_index;
+
+        let open = async {
+            let existing: Vec<U256> = self
+                .get_pair_positions(trader, pair_index)
+                .await?
+                .into_iter()
+                .map(|position| position.trade_id)
+                .collect();
+
+            let tx_hash = self.place_order(params, builder_fee).await?;
+            let receipt = self.wait_for_receipt(tx_hash).await?;
+            if !receipt.status() {
+                let reason = self
+                    .revert_reason(tx_hash, receipt.block_number)
+                    .await
+                    .unwrap_or_else(|| "unknown reason".to_string());
+                eyre::bail!("Order transaction {} reverted: {}", tx_hash, reason);
             }
 
-            // Query each possible trade index
-            for trade_index in 0..max_trades_per_pair {
-                if let Some(position) = self.get_position(trader, pair_index, trade_index).await? {
-                    positions.push(position);
+            let interval = self.poll_config.unwrap_or_default().interval;
+            loop {
+                let filled = self
+                    .get_pair_positions(trader, pair_index)
+                    .await?
+                    .into_iter()
+                    .find(|position| !existing.contains(&position.trade_id));
+                if let Some(position) = filled {
+                    return Ok(position);
                 }
+                tokio::time::sleep(interval).await;
             }
-        }
-
-        Ok(positions)
-    }
-
-    /// Get open trades count for a specific pair
-    async fn get_open_trades_count(&self, trader: Address, pair_index: u16) -> Result<u32> {
-        let call = ITradingStorage::openTradesCountCall {
-            trader,
-            pairIndex: pair_index,
         };
 
-        let result: Bytes = self
-            .provider
-            .call(
-                alloy::rpc::types::TransactionRequest::default()
-                    .with_to(self.config.trading_storage)
-                    .with_input(call.abi_encode()),
+        tokio::time::timeout(timeout, open).await.map_err(|_| {
+            eyre::eyre!(
+                "Timed out after {:?} waiting for order on pair {} to be filled",
+                timeout,
+                pair_index
             )
-            .await
-            .context("Failed to get open trades count")?;
-
-        let decoded = ITradingStorage::openTradesCountCall::abi_decode_returns(&result)
-            .context("Failed to decode open trades count")?;
-
-        Ok(decoded)
+        })?
     }
 
-    /// Get a single position from contract
-    async fn get_position(
-        &self,
-        trader: Address,
-        pair_index: u16,
-        trade_index: u8,
-    ) -> Result<Option<Position>> {
-        let call = ITradingStorage::getOpenTradeCall {
-            trader,
-            pairIndex: pair_index,
-            index: trade_index,
-        };
-
-        let result: Bytes = self
+    /// Replay a failed transaction against its parent block to recover the revert reason
+    ///
+    /// The replay runs on the state before the transaction's block, so it can
+    /// miss reasons caused by earlier transactions in the same block.
+    async fn revert_reason(&self, tx_hash: TxHash, block_number: Option<u64>) -> Option<String> {
+        let tx = self
             .provider
-            .call(
-                alloy::rpc::types::TransactionRequest::default()
-                    .with_to(self.config.trading_storage)
-                    .with_input(call.abi_encode()),
-            )
+            .get_transaction_by_hash(tx_hash)
             .await
-            .context("Failed to get open trade")?;
-
-        let trade = ITradingStorage::getOpenTradeCall::abi_decode_returns(&result)
-            .context("Failed to decode open trade")?;
+            .ok()??;
+        let block = block_number?.saturating_sub(1);
 
-        // Check if position is open (collateral > 0)
-        if trade.collateral == U256::ZERO {
-            return Ok(None);
+        match self
+            .provider
+            .call(tx.into_request())
+            .block(block.into())
+            .await
+        {
+            Ok(_) => None,
+            Err(e) => e
+                .as_error_resp()
+                .and_then(|resp| resp.as_revert_data())
+                .and_then(|data| alloy::sol_types::decode_revert_reason(&data))
+                .or_else(|| Some(e.to_string())),
         }
+    }
 
-        // Convert to Position struct
-        let collateral = crate::constants::unscale_from_decimals(
-            trade.collateral,
-            crate::constants::USDC_DECIMALS,
+    /// Check that the signer may open a trade for `trader` and that the
+    /// trader has approved enough USDC to TradingStorage
+    async fn check_delegation(&self, trader: Address, collateral: U256) -> Result<()> {
+        let (delegate, allowance) = tokio::try_join!(
+            self.view_call(self.config.trading, ITrading::delegationsCall { trader }),
+            self.view_call(
+                self.config.usdc,
+                IERC20::allowanceCall {
+                    owner: trader,
+                    spender: self.config.trading_storage,
+                }
+            ),
+        )?;
+
+        eyre::ensure!(
+            delegate == self.address(),
+            "Signer {} is not the approved delegate of {}",
+            self.address(),
+            trader
         );
-        let leverage = trade.leverage as f64 / 100.0;
-        let open_price = crate::constants::unscale_from_decimals(
-            U256::from(trade.openPrice),
-            crate::constants::PRICE_DECIMALS,
+        eyre::ensure!(
+            allowance >= collateral,
+            "Trader {} has not approved enough USDC to TradingStorage",
+            trader
         );
+        Ok(())
+    }
 
-        // Convert tp and sl (0 means not set)
-        let take_profit = if trade.tp != crate::types::U192::ZERO {
-            Some(crate::constants::unscale_from_decimals(
-                U256::from(trade.tp),
-                crate::constants::PRICE_DECIMALS,
-            ))
-        } else {
-            None
-        };
+    /// Build the Trading transaction for an order, wrapping it in
+    /// `delegatedAction` when it is opened for another trader
+    fn open_trade_tx(
+        &self,
+        params: &PlaceOrderParams,
+        builder_fee: Option<BuilderFeeParams>,
+    ) -> TxRequest {
+        let trader = params.trader.unwrap_or_else(|| self.address());
+        let trade_index = params.trade_index.unwrap_or(0);
+        let builder_fee = builder_fee.or_else(|| self.default_builder_fee.clone());
+        let data = params.encode_calldata(trader, trade_index, builder_fee);
 
-        let stop_loss = if trade.sl != crate::types::U192::ZERO {
-            Some(crate::constants::unscale_from_decimals(
-                U256::from(trade.sl),
-                crate::constants::PRICE_DECIMALS,
-            ))
+        let data = if trader == self.address() {
+            data
         } else {
-            None
+            let call = ITrading::delegatedActionCall {
+                trader,
+                call_data: data,
+            };
+            Bytes::from(call.abi_encode())
         };
+        TxRequest::new(self.config.trading, data)
+    }
 
-        Ok(Some(Position {
-            trader: trade.trader,
-            pair_index: trade.pairIndex,
+    /// Resolve `SlippageMode::Auto` from fresh price samples of the order's pair
+    ///
+    /// Falls back to the mode's ceiling if the pair or prices can't be fetched.
+    async fn resolve_auto_slippage(&self, mut params: PlaceOrderParams) -> PlaceOrderParams {
+        if !matches!(params.slippage_mode, SlippageMode::Auto { .. }) || params.slippage.is_some() {
+            return params;
+        }
+
+        let samples = async {
+            let pair = self.subgraph()?.get_pair(params.pair_index).await?;
+            let mut samples = Vec::with_capacity(AUTO_SLIPPAGE_SAMPLES);
+            for i in 0..AUTO_SLIPPAGE_SAMPLES {
+                if i > 0 {
+                    tokio::time::sleep(Duration::from_millis(AUTO_SLIPPAGE_SAMPLE_INTERVAL_MS))
+                        .await;
+                }
+                samples.push(
+                    get_price_data_with_http_config(&pair.from, &pair.to, &self.config.http)
+                        .await?,
+                );
+            }
+            Ok::<_, eyre::Report>(samples)
+        };
+
+        match samples.await {
+            Ok(samples) => params.slippage = params.slippage_mode.resolve(&samples),
+            Err(e) => tracing::warn!("Auto slippage sampling failed, using ceiling: {:#}", e),
+        }
+        params
+    }
+
+    /// Build the `openTrade` transaction for an order without sending it
+    ///
+    /// Validates the params and returns the request targeting the Trading
+    /// contract, for callers that submit through their own relayer or wallet.
+    /// Note that no USDC allowance or delegation is checked, and unresolved
+    /// auto slippage is encoded at its ceiling.
+    pub fn open_trade_raw(
+        &self,
+        params: &PlaceOrderParams,
+        builder_fee: Option<BuilderFeeParams>,
+    ) -> Result<TxRequest> {
+        let params = self.with_order_defaults(params.clone());
+        params.validate()?;
+        Ok(self.open_trade_tx(&params, builder_fee))
+    }
+
+    /// Fill an order's unset fixed slippage from the client default
+    fn with_order_defaults(&self, mut params: PlaceOrderParams) -> PlaceOrderParams {
+        if params.slippage.is_none() && params.slippage_mode == SlippageMode::Fixed {
+            params.slippage = self.default_slippage;
+        }
+        params
+    }
+
+    /// Close a trade at market price
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Close trade parameters
+    ///
+    /// # Returns
+    ///
+    /// Transaction hash of the close order
+    pub async fn close_trade(&self, mut params: CloseTradeParams) -> Result<TxHash> {
+        params.validate()?;
+        if params.slippage.is_none() {
+            params.slippage = self.default_slippage;
+        }
+        self.get_protocol_limits()
+            .await?
+            .check_index(params.pair_index, params.trade_index)?;
+
+        let data = params.encode_calldata();
+
+        let tx = TxRequest::new(self.config.trading, data);
+        let result = self.send(tx).await.context("Failed to close trade");
+        self.invalidate_own_positions();
+        result
+    }
+
+    /// Close a trade at market price, returning a handle to await it
+    ///
+    /// See [`Self::close_trade`].
+    pub async fn close_trade_pending(
+        &self,
+        params: CloseTradeParams,
+    ) -> Result<PendingTransaction<'_, S>> {
+        let hash = self.close_trade(params).await?;
+        Ok(self.pending_transaction(hash))
+    }
+
+    /// Close a trade at market price and wait for it to be executed
+    ///
+    /// Market closes settle in a later oracle callback. After the request
+    /// confirms, this takes the order ID from its `PriceRequested` event and
+    /// polls the subgraph until that order is executed or cancelled. The
+    /// proceeds are the USDC transfers to the signer in the execution
+    /// transaction only, which covers both the TradingStorage collateral leg
+    /// and any profit paid out of the vault.
+    ///
+    /// Requires a subgraph URL in the config.
+    ///
+    /// # Returns
+    ///
+    /// The realized proceeds and PnL. An oracle-cancelled close is tagged
+    /// [`OstiumError::RetrySafe`] since the trade is left untouched; a timeout
+    /// is tagged [`OstiumError::RetryUnsafe`]
+    pub async fn close_trade_confirmed(
+        &self,
+        params: CloseTradeParams,
+        timeout: Duration,
+    ) -> Result<CloseResult> {
+        params.validate()?;
+        let subgraph = self.subgraph()?;
+        let trader = self.address();
+        let (pair_index, trade_index) = (params.pair_index, params.trade_index);
+        let before = self
+            .get_position(trader, pair_index, trade_index)
+            .await?
+            .ok_or_else(|| {
+                eyre::eyre!("No open trade at pair {} index {}", pair_index, trade_index)
+            })?;
+        let closed_collateral = before.collateral * params.close_percentage / 100.0;
+
+        let tx_hash = self.close_trade(params).await?;
+        let interval = self.poll_config.unwrap_or_default().interval;
+        let settle = async {
+            let receipt = self.wait_for_receipt(tx_hash).await?;
+            eyre::ensure!(receipt.status(), "Close request {} reverted", tx_hash);
+            let order_id = receipt
+                .inner
+                .logs()
+                .iter()
+                .filter(|log| log.address() == self.config.trading)
+                .find_map(|log| log.log_decode::<ITrading::PriceRequested>().ok())
+                .map(|log| log.inner.data.orderId)
+                .ok_or_else(|| {
+                    eyre::eyre!("Close request {} has no PriceRequested event", tx_hash)
+                })?;
+
+            let execution_tx: TxHash = loop {
+                match subgraph.get_order(order_id).await {
+                    Ok(Some(order)) if order.is_cancelled => {
+                        return Err(Report::new(OstiumError::RetrySafe(format!(
+                            "Close order {} was cancelled by the oracle",
+                            order_id
+                        ))));
+                    }
+                    Ok(Some(SubgraphOrder {
+                        executed_tx: Some(tx),
+                        ..
+                    })) => break tx.parse().context("Invalid close execution hash")?,
+                    Ok(_) => {}
+                    Err(e) => tracing::debug!("Order lookup for close {} failed: {}", tx_hash, e),
+                }
+                tokio::time::sleep(interval).await;
+            };
+
+            let execution = self
+                .provider
+                .get_transaction_receipt(execution_tx)
+                .await
+                .context("Failed to read close execution")?
+                .ok_or_else(|| eyre::eyre!("Close execution {} not found", execution_tx))?;
+            Ok((execution_tx, execution))
+        };
+
+        let (execution_tx, execution) =
+            tokio::time::timeout(timeout, settle).await.map_err(|_| {
+                Report::new(OstiumError::RetryUnsafe(format!(
+                    "Close {} not executed within {:?}",
+                    tx_hash, timeout
+                )))
+            })??;
+        self.settle_cached_positions(trader);
+
+        let proceeds: U256 = execution
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == self.config.usdc)
+            .filter_map(|log| log.log_decode::<IERC20::Transfer>().ok())
+            .filter(|transfer| transfer.inner.data.to == trader)
+            .map(|transfer| transfer.inner.data.value)
+            .sum();
+
+        Ok(CloseResult::new(
+            tx_hash,
+            Some(execution_tx),
+            closed_collateral,
+            unscale_from_decimals(proceeds, USDC_DECIMALS),
+        ))
+    }
+
+    /// Cancel a pending limit or stop order
+    ///
+    /// # Arguments
+    ///
+    /// * `pair_index` - Trading pair of the order
+    /// * `index` - Order index within the pair
+    pub async fn cancel_order(&self, pair_index: u16, index: u8) -> Result<TxHash> {
+        self.get_protocol_limits()
+            .await?
+            .check_index(pair_index, index)?;
+
+        let call = ITrading::cancelOpenLimitOrderCall {
+            pairIndex: pair_index,
+            index,
+        };
+
+        let tx = TxRequest::new(self.config.trading, Bytes::from(call.abi_encode()));
+        self.send(tx).await.context("Failed to cancel order")
+    }
+
+    /// Cancel every pending limit and stop order of the signer
+    ///
+    /// Orders are cancelled one at a time. If a cancellation fails, the error
+    /// reports which order failed and how many were already cancelled.
+    ///
+    /// # Returns
+    ///
+    /// Transaction hashes of the cancellations, in pair/index order
+    pub async fn cancel_all_orders(&self) -> Result<Vec<TxHash>> {
+        let orders = self.get_open_orders(None).await?;
+        let mut tx_hashes = Vec::with_capacity(orders.len());
+
+        for order in orders {
+            let tx_hash = self
+                .cancel_order(order.pair_index, order.index)
+                .await
+                .wrap_err_with(|| {
+                    format!(
+                        "Failed to cancel order {}/{} after cancelling {} of the signer's orders",
+                        order.pair_index,
+                        order.index,
+                        tx_hashes.len()
+                    )
+                })?;
+            tx_hashes.push(tx_hash);
+        }
+
+        Ok(tx_hashes)
+    }
+
+    /// Get the protocol's pair count and trades-per-pair limit
+    ///
+    /// Read from TradingStorage on first use and cached until
+    /// [`Self::refresh_protocol_params`].
+    pub async fn get_protocol_limits(&self) -> Result<ProtocolLimits> {
+        self.protocol_limits
+            .get_or_try_insert_with((), || async {
+                let (pairs_count, max_trades_per_pair) = tokio::try_join!(
+                    self.view_call(
+                        self.config.trading_storage,
+                        ITradingStorage::pairsCountCall {}
+                    ),
+                    self.view_call(
+                        self.config.trading_storage,
+                        ITradingStorage::maxTradesPerPairCall {}
+                    ),
+                )
+                .context("Failed to get protocol limits")?;
+
+                Ok(ProtocolLimits {
+                    pairs_count,
+                    max_trades_per_pair,
+                })
+            })
+            .await
+    }
+
+    /// Get the maximum open trades per trader per pair (cached)
+    pub async fn get_max_trades_per_pair(&self) -> Result<u8> {
+        Ok(self.get_protocol_limits().await?.max_trades_per_pair)
+    }
+
+    /// Get how many more trades the signer can open on a pair
+    ///
+    /// Zero means the pair is full until one of its trades closes.
+    pub async fn available_trade_slots(&self, pair_index: u16) -> Result<u8> {
+        let (limits, open) = tokio::try_join!(
+            self.get_protocol_limits(),
+            self.get_open_trades_count(self.address(), pair_index),
+        )?;
+        limits.check_index(pair_index, 0)?;
+        Ok(limits.available_slots(open))
+    }
+
+    /// Run a read-only contract call and decode the result
+    async fn view_call<C: SolCall>(&self, to: Address, call: C) -> Result<C::Return> {
+        let result: Bytes = self
+            .provider
+            .call(
+                alloy::rpc::types::TransactionRequest::default()
+                    .with_to(to)
+                    .with_input(call.abi_encode()),
+            )
+            .await
+            .wrap_err_with(|| format!("Failed to call {}", C::SIGNATURE))?;
+
+        C::abi_decode_returns(&result)
+            .wrap_err_with(|| format!("Failed to decode {}", C::SIGNATURE))
+    }
+
+    // ========== Pair Queries ==========
+
+    /// Get a pair's configuration and current market in one snapshot
+    ///
+    /// Combines the pair's symbol, leverage limits, fees, min size, and open
+    /// interest (from the subgraph) with its current bid/ask (from the price feed).
+    pub async fn get_pair_info(&self, pair_index: u16) -> Result<PairInfo> {
+        let pair = self.subgraph()?.get_pair(pair_index).await?;
+        let price =
+            get_price_data_with_http_config(&pair.from, &pair.to, &self.config.http).await?;
+        PairInfo::from_parts(&pair, &price)
+    }
+
+    /// Get the current index of the pair with the given symbols, e.g. ("BTC", "USD")
+    ///
+    /// Symbols are matched case-insensitively against the subgraph's pair
+    /// list, since indices can differ between deployments. Lookups are cached
+    /// for an hour. Requires a configured subgraph.
+    pub async fn resolve_pair(&self, from: &str, to: &str) -> Result<u16> {
+        let key = (from.to_uppercase(), to.to_uppercase());
+        if let Some(pair_index) = self.pair_indices.get(&key) {
+            return Ok(pair_index);
+        }
+
+        for pair in self.subgraph()?.get_pairs().await? {
+            let pair_index = pair
+                .id
+                .parse()
+                .with_context(|| format!("Invalid pair id: {}", pair.id))?;
+            self.pair_indices.insert(
+                (pair.from.to_uppercase(), pair.to.to_uppercase()),
+                pair_index,
+            );
+        }
+        self.pair_indices
+            .get(&key)
+            .ok_or_else(|| eyre::eyre!("No pair {}/{} listed", from, to))
+    }
+
+    /// Replace an order's pair symbols with the resolved pair index
+    async fn resolve_order_pair(&self, mut params: PlaceOrderParams) -> Result<PlaceOrderParams> {
+        if let Some((from, to)) = params.pair_symbol.take() {
+            params.pair_index = self.resolve_pair(&from, &to).await?;
+        }
+        Ok(params)
+    }
+
+    /// Validate order parameters, including the pair's minimum size
+    ///
+    /// Runs [`PlaceOrderParams::validate`], then checks the collateral against
+    /// the pair minimum from [`Self::get_min_collateral`], which catches orders
+    /// the contract would revert as below minimum. The minimum check needs the
+    /// subgraph; without one, or if it can't be reached, it is skipped with a
+    /// warning so a subgraph outage doesn't block trading.
+    pub async fn validate_order(&self, params: &PlaceOrderParams) -> Result<()> {
+        params.validate()?;
+        if self.subgraph.is_none() {
+            return Ok(());
+        }
+
+        match self
+            .get_min_collateral(params.pair_index, params.leverage)
+            .await
+        {
+            Ok(min_collateral) => params.check_min_collateral(min_collateral),
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping minimum collateral check for pair {}: {:#}",
+                    params.pair_index,
+                    e
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the minimum collateral for opening a position on a pair
+    ///
+    /// Pairs enforce a minimum position size (collateral * leverage) that varies
+    /// by asset class, so the collateral minimum depends on the leverage.
+    /// Requires a configured subgraph.
+    pub async fn get_min_collateral(&self, pair_index: u16, leverage: f64) -> Result<f64> {
+        let pair = self.subgraph()?.get_pair(pair_index).await?;
+        let min_position_size = parse_scaled(&pair.fee.min_lev_pos, USDC_DECIMALS.into())?;
+        if leverage <= 0.0 {
+            return Ok(min_position_size);
+        }
+        Ok(min_position_size / leverage)
+    }
+
+    /// Estimate open and close fees for an order before placing it
+    ///
+    /// Uses the pair's taker fee rate on the order's notional. This is a
+    /// conservative estimate: orders that reduce open-interest skew may be
+    /// charged the lower maker rate.
+    ///
+    /// No referral or volume-tier discount is applied, so traders with one
+    /// pay less than estimated.
+    pub async fn estimate_trade_fees(&self, params: &PlaceOrderParams) -> Result<TradeFees> {
+        let params = self.resolve_order_pair(params.clone()).await?;
+        let pair = self.subgraph()?.get_pair(params.pair_index).await?;
+        let taker_fee_percent = parse_scaled(&pair.taker_fee_p, 6)?;
+        Ok(TradeFees::new(
+            params.collateral,
+            params.leverage,
+            taker_fee_percent,
+        ))
+    }
+
+    /// Recommend a slippage tolerance for an order from spread and liquidity
+    ///
+    /// Combines the pair's live bid/ask spread with the order's notional
+    /// against the open interest still available on its side; see
+    /// [`SlippageRecommendation::new`]. Requires a configured subgraph, whose
+    /// open interest can lag the chain slightly.
+    pub async fn recommend_slippage(
+        &self,
+        params: &PlaceOrderParams,
+    ) -> Result<SlippageRecommendation> {
+        let params = self.resolve_order_pair(params.clone()).await?;
+        let pair = self.get_pair_info(params.pair_index).await?;
+
+        Ok(SlippageRecommendation::new(
+            pair.spread_percent,
+            params.collateral * params.leverage,
+            pair.available_oi(params.is_long),
+        ))
+    }
+
+    /// Assemble everything a "confirm order" screen shows before placing an order
+    ///
+    /// Combines pair info at the live price, the expected and worst-case fill,
+    /// fees, the resulting liquidation price and the pair's minimum collateral
+    /// with whether the trader's USDC balance and allowance and the signer's
+    /// ETH cover the order. Validation failures are reported in the ticket
+    /// rather than returned. Requires a configured subgraph.
+    pub async fn trade_ticket(&self, params: &PlaceOrderParams) -> Result<TradeTicket> {
+        let params = self.with_order_defaults(self.resolve_order_pair(params.clone()).await?);
+        let trader = params.trader.unwrap_or_else(|| self.address());
+        let usdc = self.config.usdc;
+        let (pair, fees, usdc_balance, allowance, eth_balance, gas_price) = tokio::try_join!(
+            self.get_pair_info(params.pair_index),
+            self.estimate_trade_fees(&params),
+            self.view_call(usdc, IERC20::balanceOfCall { account: trader }),
+            self.view_call(
+                usdc,
+                IERC20::allowanceCall {
+                    owner: trader,
+                    spender: self.config.trading_storage,
+                }
+            ),
+            self.get_eth_balance(),
+            async {
+                self.provider
+                    .get_gas_price()
+                    .await
+                    .context("Failed to get gas price")
+            },
+        )?;
+
+        let entry_price =
+            params
+                .open_price
+                .unwrap_or(if params.is_long { pair.ask } else { pair.bid });
+        let min_collateral = pair.min_collateral(params.leverage);
+        let validation_error = params
+            .validate()
+            .and_then(|_| params.check_min_collateral(min_collateral))
+            .err()
+            .map(|e| e.to_string());
+
+        let allowance_sufficient = allowance >= params.scaled_collateral();
+        let gas_cost_eth = if allowance_sufficient && validation_error.is_none() {
+            let tx = self.open_trade_tx(&params, None);
+            match self
+                .estimate_gas_with_buffer(&tx, self.gas_buffer_percent)
+                .await
+            {
+                Ok(gas) => Some(gas as f64 * gas_price as f64 / 1e18),
+                Err(e) => {
+                    tracing::debug!("Gas estimation for trade ticket failed: {:#}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(TradeTicket {
+            entry_price,
+            worst_case_price: params.worst_case_price(entry_price),
+            fees,
+            collateral: params.collateral,
+            min_collateral,
+            liquidation_price: params.liquidation_price(entry_price),
+            usdc_balance: unscale_from_decimals(usdc_balance, USDC_DECIMALS),
+            allowance_sufficient,
+            eth_balance: unscale_from_decimals(eth_balance, 18),
+            gas_cost_eth,
+            validation_error,
+            pair,
+        })
+    }
+
+    /// Preview closing a position without sending a transaction
+    ///
+    /// Reads the signer's position and quotes the close at the live price on
+    /// the side the close executes against (bid for longs, ask for shorts),
+    /// charging the pair's taker fee on the closed notional.
+    pub async fn simulate_close(&self, params: &CloseTradeParams) -> Result<CloseQuote> {
+        params.validate()?;
+        let position = self
+            .get_open_position(params.pair_index, params.trade_index)
+            .await?;
+
+        let pair = self.subgraph()?.get_pair(params.pair_index).await?;
+        let taker_fee_percent = parse_scaled(&pair.taker_fee_p, 6)?;
+        let price =
+            get_price_data_with_http_config(&pair.from, &pair.to, &self.config.http).await?;
+        let close_price = if position.is_long {
+            price.bid
+        } else {
+            price.ask
+        };
+
+        Ok(CloseQuote::new(
+            &position,
+            params.close_percentage,
+            close_price,
+            taker_fee_percent,
+        ))
+    }
+
+    /// Get the break-even price of one of the signer's positions
+    ///
+    /// Charges the pair's taker fee on open and close, with no referral or
+    /// volume-tier discount. Funding and rollover accrued since opening are
+    /// not read and are treated as zero.
+    pub async fn get_break_even_price(&self, pair_index: u16, trade_index: u8) -> Result<f64> {
+        let position = self.get_open_position(pair_index, trade_index).await?;
+
+        let pair = self.subgraph()?.get_pair(pair_index).await?;
+        let taker_fee_percent = parse_scaled(&pair.taker_fee_p, 6)?;
+        let fees = TradeFees::new(position.collateral, position.leverage, taker_fee_percent);
+
+        Ok(position.break_even_price(fees.open_fee, fees.close_fee, 0.0))
+    }
+
+    /// Get the effective leverage of one of the signer's positions at the live price
+    ///
+    /// Notional divided by current equity (collateral plus unrealized PnL),
+    /// priced on the side a close executes against (bid for longs, ask for
+    /// shorts). See [`Position::effective_leverage`].
+    pub async fn get_effective_leverage(&self, pair_index: u16, trade_index: u8) -> Result<f64> {
+        let position = self.get_open_position(pair_index, trade_index).await?;
+        let pair = self.subgraph()?.get_pair(pair_index).await?;
+        let price =
+            get_price_data_with_http_config(&pair.from, &pair.to, &self.config.http).await?;
+        let close_price = if position.is_long {
+            price.bid
+        } else {
+            price.ask
+        };
+        Ok(position.effective_leverage(close_price))
+    }
+
+    /// Preview adding (`delta > 0`) or removing (`delta < 0`) USDC collateral
+    /// on one of the signer's positions
+    ///
+    /// Returns the resulting leverage and liquidation price so a margin change
+    /// can be checked before it is submitted. Nothing is sent on-chain.
+    pub async fn preview_collateral_change(
+        &self,
+        pair_index: u16,
+        trade_index: u8,
+        delta: f64,
+    ) -> Result<CollateralPreview> {
+        let position = self.get_open_position(pair_index, trade_index).await?;
+        CollateralPreview::new(&position, delta)
+    }
+
+    // ========== Event Subscriptions ==========
+
+    /// Wait for a pair's price to meet an alert condition, then call `on_fire` once
+    ///
+    /// Polls the price feed with the client's HTTP settings at the alert's
+    /// interval. See [`PriceAlert`] for debounce behavior.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let alert = PriceAlert::new(AlertCondition::CrossesUp(70_000.0));
+    /// client.price_alert("BTC", "USD", alert, |price| println!("BTC at {price}")).await?;
+    /// ```
+    pub async fn price_alert(
+        &self,
+        from: &str,
+        to: &str,
+        alert: PriceAlert,
+        on_fire: impl FnOnce(f64),
+    ) -> Result<f64> {
+        alert.watch(from, to, &self.config.http, on_fire).await
+    }
+
+    /// Subscribe to the signer's trade events on the Trading contract
+    ///
+    /// Streams `PriceRequested` events sent by the signer's address, decoded
+    /// into open and close requests. Requires a WebSocket RPC URL. If the
+    /// subscription drops, it is re-established automatically.
+    pub fn subscribe_trade_events(&self) -> Result<impl Stream<Item = TradeEvent> + '_> {
+        eyre::ensure!(
+            self.config.is_websocket(),
+            "Event subscriptions require a ws:// or wss:// RPC URL"
+        );
+
+        let filter = Filter::new()
+            .address(self.config.trading)
+            .event_signature(ITrading::PriceRequested::SIGNATURE_HASH)
+            .topic2(self.address().into_word());
+
+        Ok(futures::stream::unfold(None, move |mut logs| {
+            let filter = filter.clone();
+            async move {
+                loop {
+                    let stream = match logs.as_mut() {
+                        Some(stream) => stream,
+                        None => match self.provider.subscribe_logs(&filter).await {
+                            Ok(subscription) => logs.insert(subscription.into_stream()),
+                            Err(e) => {
+                                tracing::warn!("Failed to subscribe to trade events: {:#}", e);
+                                tokio::time::sleep(Duration::from_secs(2)).await;
+                                continue;
+                            }
+                        },
+                    };
+
+                    match stream.next().await {
+                        Some(log) => {
+                            if let Some(event) = decode_trade_event(&log) {
+                                return Some((event, logs));
+                            }
+                        }
+                        None => {
+                            tracing::warn!("Trade event subscription closed, resubscribing");
+                            logs = None;
+                        }
+                    }
+                }
+            }
+        }))
+    }
+
+    // ========== Position Queries (Direct Contract Calls) ==========
+
+    /// Get all open positions for an address directly from TradingStorage contract
+    ///
+    /// Scans all trading pairs to find open positions, with pair counts and
+    /// trades fetched concurrently (see [`Self::with_read_concurrency`]).
+    /// Results are ordered by pair and trade index. If the positions cache is
+    /// enabled, a fresh cached result is returned instead.
+    ///
+    /// Fails if any pair can't be read; use [`Self::get_positions_partial`]
+    /// to get the positions that could be read along with the failures.
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    ///
+    /// # Returns
+    ///
+    /// Vector of Position structs representing open trades
+    pub async fn get_positions(&self, trader: Option<Address>) -> Result<Vec<Position>> {
+        let trader = trader.unwrap_or_else(|| self.address());
+
+        let scan = || async {
+            self.get_positions_partial(Some(trader))
+                .await?
+                .into_positions()
+        };
+        match self.positions_cache_for(trader) {
+            Some(cache) => cache.get_or_try_insert_with(trader, scan).await,
+            None => scan().await,
+        }
+    }
+
+    /// Write the signer's open positions as newline-delimited JSON
+    ///
+    /// Each line is one [`Position`] plus the snapshot's Unix `timestamp` and
+    /// the `block_number` read just before the scan, so every record in an
+    /// export shares the same point in time.
+    ///
+    /// # Returns
+    ///
+    /// Number of positions written
+    pub async fn export_positions_ndjson(&self, mut writer: impl Write) -> Result<usize> {
+        let block_number = self
+            .provider
+            .get_block_number()
+            .await
+            .context("Failed to get block number")?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("System time error")?
+            .as_secs();
+        let positions = self.get_positions(None).await?;
+
+        for position in &positions {
+            let line = position.to_json_line(timestamp, block_number)?;
+            writeln!(writer, "{}", line).context("Failed to write position")?;
+        }
+        writer.flush().context("Failed to flush positions")?;
+
+        Ok(positions.len())
+    }
+
+    /// Get the trader's open positions as a snapshot keyed by trade ID
+    ///
+    /// Pass two snapshots to [`diff_positions`](crate::types::diff_positions)
+    /// to find opened, closed and modified trades.
+    pub async fn get_positions_snapshot(
+        &self,
+        trader: Option<Address>,
+    ) -> Result<PositionSnapshot> {
+        Ok(PositionSnapshot::new(self.get_positions(trader).await?))
+    }
+
+    /// Read a fresh snapshot and diff it against a previous one
+    ///
+    /// Returns the new snapshot, to pass in on the next poll, along with
+    /// what changed since `previous`.
+    pub async fn get_positions_changed_since(
+        &self,
+        previous: &PositionSnapshot,
+        trader: Option<Address>,
+    ) -> Result<(PositionSnapshot, PositionDiff)> {
+        let snapshot = self.get_positions_snapshot(trader).await?;
+        let diff = diff_positions(previous, &snapshot);
+        Ok((snapshot, diff))
+    }
+
+    /// Scan all pairs for open positions, tolerating per-pair read failures
+    ///
+    /// A failed RPC read only affects its pair: the scan continues and the
+    /// pair is reported in [`PositionScanResult::errors`]. Complete results
+    /// are stored in the positions cache if it is enabled; the cache is not
+    /// read, so every call scans.
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    pub async fn get_positions_partial(
+        &self,
+        trader: Option<Address>,
+    ) -> Result<PositionScanResult> {
+        let trader = trader.unwrap_or_else(|| self.address());
+        // A write during the scan invalidates it, so the result isn't cached
+        let generation = self
+            .positions_cache
+            .as_ref()
+            .map(|cache| cache.generation(&trader));
+        let max_trades = self.get_max_trades_per_pair().await?;
+        let permits = Semaphore::new(self.read_concurrency.max(1));
+        let mut errors = Vec::new();
+
+        // Check open trades count for every pair concurrently
+        let counts = futures::future::join_all((0..MAX_SCANNED_PAIRS).map(|pair_index| {
+            let permits = &permits;
+            async move {
+                let _permit = permits.acquire().await?;
+                let count = self.get_open_trades_count(trader, pair_index).await?;
+                Ok::<_, eyre::Report>(count)
+            }
+        }))
+        .await;
+
+        // Query each possible trade index of the non-empty pairs concurrently
+        let mut open_pairs = Vec::new();
+        for (pair_index, count) in (0..MAX_SCANNED_PAIRS).zip(counts) {
+            match count {
+                Ok(0) => {}
+                Ok(_) => open_pairs.push(pair_index),
+                Err(e) => errors.push((pair_index, e)),
+            }
+        }
+        let slots: Vec<(u16, u8)> = open_pairs
+            .into_iter()
+            .flat_map(|pair_index| {
+                (0..max_trades).map(move |trade_index| (pair_index, trade_index))
+            })
+            .collect();
+        let results = futures::future::join_all(slots.iter().map(|&(pair_index, trade_index)| {
+            let permits = &permits;
+            async move {
+                let _permit = permits.acquire().await?;
+                self.get_position(trader, pair_index, trade_index).await
+            }
+        }))
+        .await;
+
+        let mut positions = Vec::new();
+        for (&(pair_index, _), result) in slots.iter().zip(results) {
+            match result {
+                Ok(position) => positions.extend(position),
+                Err(e) => {
+                    if !errors.iter().any(|(failed, _)| *failed == pair_index) {
+                        errors.push((pair_index, e));
+                    }
+                }
+            }
+        }
+        errors.sort_by_key(|(pair_index, _)| *pair_index);
+
+        let positions = dedup_positions(positions);
+        let result = PositionScanResult { positions, errors };
+        if let (Some(cache), Some(generation)) = (
+            self.positions_cache_for(trader)
+                .filter(|_| result.is_complete()),
+            generation,
+        ) {
+            cache.insert_if_current(trader, result.positions.clone(), generation);
+        }
+
+        Ok(result)
+    }
+
+    /// Get total collateral locked in open positions, in USDC
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    pub async fn get_total_collateral_locked(&self, trader: Option<Address>) -> Result<f64> {
+        Ok(self.get_exposure(trader).await?.total_collateral)
+    }
+
+    /// Get total notional (collateral * leverage) of open positions, in USDC
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    pub async fn get_total_notional_exposure(&self, trader: Option<Address>) -> Result<f64> {
+        Ok(self.get_exposure(trader).await?.gross_notional())
+    }
+
+    /// Get locked collateral and long/short notional across open positions
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    pub async fn get_exposure(&self, trader: Option<Address>) -> Result<PositionExposure> {
+        let positions = self.get_positions(trader).await?;
+        Ok(PositionExposure::from_positions(&positions))
+    }
+
+    /// Get open positions grouped by trading pair
+    ///
+    /// Built on [`Self::get_positions`]; positions within a pair stay in trade
+    /// index order. Use [`Self::get_pair_symbols`] to label the keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    pub async fn get_positions_grouped(
+        &self,
+        trader: Option<Address>,
+    ) -> Result<HashMap<u16, Vec<Position>>> {
+        let positions = self.get_positions(trader).await?;
+        Ok(group_positions_by_pair(positions).into_iter().collect())
+    }
+
+    /// Get open positions grouped by trading pair, sorted by pair index
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    pub async fn get_positions_grouped_sorted(
+        &self,
+        trader: Option<Address>,
+    ) -> Result<Vec<(u16, Vec<Position>)>> {
+        let positions = self.get_positions(trader).await?;
+        Ok(group_positions_by_pair(positions))
+    }
+
+    /// Get the symbol (e.g. "BTC/USD") of every pair listed in the subgraph
+    pub async fn get_pair_symbols(&self) -> Result<HashMap<u16, String>> {
+        let pairs = self.subgraph()?.get_pairs().await?;
+        pairs
+            .into_iter()
+            .map(|pair| {
+                let pair_index: u16 = pair.id.parse().context("Invalid pair index")?;
+                Ok((pair_index, format!("{}/{}", pair.from, pair.to)))
+            })
+            .collect()
+    }
+
+    /// Get the history of one of the signer's trades
+    ///
+    /// Assembles the open, partial closes, and final close (market, take
+    /// profit, stop loss, or liquidation) of the latest trade in the slot from
+    /// the subgraph, alongside the position as currently stored on-chain.
+    /// TP/SL modifications aren't orders, so they are read from the Trading
+    /// contract's `TpUpdated`/`SlUpdated` logs between the open and the close.
+    ///
+    /// # Arguments
+    ///
+    /// * `pair_index` - Trading pair index
+    /// * `trade_index` - Trade index within the pair
+    pub async fn get_trade_lifecycle(
+        &self,
+        pair_index: u16,
+        trade_index: u8,
+    ) -> Result<TradeLifecycle> {
+        let trader = self.address();
+        let subgraph = self.subgraph()?;
+        let (orders, current) = tokio::try_join!(
+            subgraph.get_trade_orders(trader, pair_index, trade_index),
+            self.get_position(trader, pair_index, trade_index),
+        )?;
+        let lifecycle = TradeLifecycle::from_orders(pair_index, trade_index, &orders, current)?;
+
+        let open_tx = lifecycle
+            .events
+            .iter()
+            .find(|event| event.kind == LifecycleEventKind::Open)
+            .and_then(|event| event.tx_hash);
+        let Some(open_tx) = open_tx else {
+            return Ok(lifecycle);
+        };
+        let close_tx = lifecycle
+            .events
+            .last()
+            .filter(|_| !lifecycle.is_open())
+            .and_then(|event| event.tx_hash);
+        let from_block = self.tx_block(open_tx).await?;
+        let to_block = match close_tx {
+            Some(tx) => Some(self.tx_block(tx).await?),
+            None => None,
+        };
+        let updates = self
+            .get_tp_sl_updates(trader, pair_index, trade_index, from_block, to_block)
+            .await?;
+        Ok(lifecycle.with_updates(updates))
+    }
+
+    /// Block a mined transaction was included in
+    async fn tx_block(&self, tx_hash: TxHash) -> Result<u64> {
+        self.provider
+            .get_transaction_receipt(tx_hash)
+            .await
+            .context("Failed to get transaction receipt")?
+            .and_then(|receipt| receipt.block_number)
+            .ok_or_else(|| eyre::eyre!("Transaction {} is not mined", tx_hash))
+    }
+
+    /// Read a trade slot's TP/SL updates from the Trading contract's logs
+    async fn get_tp_sl_updates(
+        &self,
+        trader: Address,
+        pair_index: u16,
+        trade_index: u8,
+        from_block: u64,
+        to_block: Option<u64>,
+    ) -> Result<Vec<LifecycleEvent>> {
+        let mut filter = Filter::new()
+            .address(self.config.trading)
+            .event_signature(vec![
+                ITrading::TpUpdated::SIGNATURE_HASH,
+                ITrading::SlUpdated::SIGNATURE_HASH,
+            ])
+            .topic2(trader.into_word())
+            .topic3(B256::from(U256::from(pair_index)))
+            .from_block(from_block);
+        if let Some(to_block) = to_block {
+            filter = filter.to_block(to_block);
+        }
+        let logs = self
+            .provider
+            .get_logs(&filter)
+            .await
+            .context("Failed to read TP/SL updates")?;
+
+        let mut block_times = HashMap::new();
+        let mut updates = Vec::new();
+        for log in logs {
+            let (kind, index, level) = if let Ok(event) = log.log_decode::<ITrading::TpUpdated>() {
+                let event = event.inner.data;
+                (
+                    LifecycleEventKind::TakeProfitUpdated,
+                    event.index,
+                    U256::from(event.newTp),
+                )
+            } else if let Ok(event) = log.log_decode::<ITrading::SlUpdated>() {
+                let event = event.inner.data;
+                (
+                    LifecycleEventKind::StopLossUpdated,
+                    event.index,
+                    U256::from(event.newSl),
+                )
+            } else {
+                continue;
+            };
+            if index != trade_index {
+                continue;
+            }
+
+            let timestamp = match (log.block_timestamp, log.block_number) {
+                (Some(timestamp), _) => timestamp,
+                (None, Some(block)) => match block_times.get(&block) {
+                    Some(&timestamp) => timestamp,
+                    None => {
+                        let timestamp = self
+                            .provider
+                            .get_block_by_number(block.into())
+                            .await
+                            .context("Failed to get block")?
+                            .ok_or_else(|| eyre::eyre!("Block {} not found", block))?
+                            .header
+                            .timestamp;
+                        block_times.insert(block, timestamp);
+                        timestamp
+                    }
+                },
+                (None, None) => continue,
+            };
+            updates.push(LifecycleEvent {
+                kind,
+                timestamp,
+                price: unscale_from_decimals(level, PRICE_DECIMALS),
+                collateral: 0.0,
+                leverage: 0.0,
+                close_percentage: None,
+                tx_hash: log.transaction_hash,
+            });
+        }
+        Ok(updates)
+    }
+
+    /// Get the price one of the signer's trades actually filled at
+    ///
+    /// Market orders execute at the oracle price in a callback, so the fill
+    /// can differ from the price the order was submitted with. For an open
+    /// trade this is the open price stored on-chain; once the slot is closed,
+    /// the open order of its latest trade is read from the subgraph instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `pair_index` - Trading pair index
+    /// * `trade_index` - Trade index within the pair
+    pub async fn get_execution_price(&self, pair_index: u16, trade_index: u8) -> Result<f64> {
+        let trader = self.address();
+        if let Some(position) = self.get_position(trader, pair_index, trade_index).await? {
+            return Ok(position.open_price);
+        }
+
+        let orders = self
+            .subgraph()?
+            .get_trade_orders(trader, pair_index, trade_index)
+            .await?;
+        TradeLifecycle::from_orders(pair_index, trade_index, &orders, None)?
+            .open_price()
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "No executed open order found for pair {} index {}",
+                    pair_index,
+                    trade_index
+                )
+            })
+    }
+
+    /// Get a trader's open positions on one pair, in trade index order
+    async fn get_pair_positions(&self, trader: Address, pair_index: u16) -> Result<Vec<Position>> {
+        let max_trades = self.get_max_trades_per_pair().await?;
+        let positions = futures::future::try_join_all(
+            (0..max_trades).map(|trade_index| self.get_position(trader, pair_index, trade_index)),
+        )
+        .await?;
+        Ok(positions.into_iter().flatten().collect())
+    }
+
+    /// Get pending limit and stop orders for an address
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    pub async fn get_open_orders(&self, trader: Option<Address>) -> Result<Vec<OpenOrder>> {
+        let trader = trader.unwrap_or_else(|| self.address());
+        let max_trades = self.get_max_trades_per_pair().await?;
+        let mut orders = Vec::new();
+
+        for pair_index in 0..MAX_SCANNED_PAIRS {
+            let call = ITradingStorage::openLimitOrdersCountCall {
+                trader,
+                pairIndex: pair_index,
+            };
+            let result: Bytes = self
+                .provider
+                .call(
+                    alloy::rpc::types::TransactionRequest::default()
+                        .with_to(self.config.trading_storage)
+                        .with_input(call.abi_encode()),
+                )
+                .await
+                .context("Failed to get open limit orders count")?;
+            let count = ITradingStorage::openLimitOrdersCountCall::abi_decode_returns(&result)
+                .context("Failed to decode open limit orders count")?;
+            if count == 0 {
+                continue;
+            }
+
+            for index in 0..max_trades {
+                let call = ITradingStorage::hasOpenLimitOrderCall {
+                    trader,
+                    pairIndex: pair_index,
+                    index,
+                };
+                let result: Bytes = self
+                    .provider
+                    .call(
+                        alloy::rpc::types::TransactionRequest::default()
+                            .with_to(self.config.trading_storage)
+                            .with_input(call.abi_encode()),
+                    )
+                    .await
+                    .context("Failed to check open limit order")?;
+                let is_open = ITradingStorage::hasOpenLimitOrderCall::abi_decode_returns(&result)
+                    .context("Failed to decode open limit order check")?;
+                if is_open {
+                    orders.push(OpenOrder {
+                        trader,
+                        pair_index,
+                        index,
+                    });
+                }
+            }
+        }
+
+        Ok(orders)
+    }
+
+    /// Get open trades count for a specific pair
+    async fn get_open_trades_count(&self, trader: Address, pair_index: u16) -> Result<u32> {
+        let call = ITradingStorage::openTradesCountCall {
+            trader,
+            pairIndex: pair_index,
+        };
+
+        let result: Bytes = self
+            .provider
+            .call(
+                alloy::rpc::types::TransactionRequest::default()
+                    .with_to(self.config.trading_storage)
+                    .with_input(call.abi_encode()),
+            )
+            .await
+            .context("Failed to get open trades count")?;
+
+        let decoded = ITradingStorage::openTradesCountCall::abi_decode_returns(&result)
+            .context("Failed to decode open trades count")?;
+
+        Ok(decoded)
+    }
+
+    /// Get a single position from contract
+    ///
+    /// Returns `None` for an empty slot. A position that fails
+    /// [`Position::validate`] is an error rather than being dropped, so a
+    /// corrupt read shows up in [`Self::get_positions_partial`]'s errors.
+    async fn get_position(
+        &self,
+        trader: Address,
+        pair_index: u16,
+        trade_index: u8,
+    ) -> Result<Option<Position>> {
+        let call = ITradingStorage::getOpenTradeCall {
+            trader,
+            pairIndex: pair_index,
+            index: trade_index,
+        };
+
+        let result: Bytes = self
+            .provider
+            .call(
+                alloy::rpc::types::TransactionRequest::default()
+                    .with_to(self.config.trading_storage)
+                    .with_input(call.abi_encode()),
+            )
+            .await
+            .context("Failed to get open trade")?;
+
+        let trade = ITradingStorage::getOpenTradeCall::abi_decode_returns(&result)
+            .context("Failed to decode open trade")?;
+
+        // Check if position is open (collateral > 0)
+        if trade.collateral == U256::ZERO {
+            return Ok(None);
+        }
+
+        let info = self.get_trade_info(trader, pair_index, trade_index).await?;
+
+        // Convert to Position struct
+        let collateral = crate::constants::unscale_from_decimals(
+            trade.collateral,
+            crate::constants::USDC_DECIMALS,
+        );
+        let leverage = trade.leverage as f64 / 100.0;
+        let open_price = crate::constants::unscale_from_decimals(
+            U256::from(trade.openPrice),
+            crate::constants::PRICE_DECIMALS,
+        );
+
+        // Convert tp and sl (0 means not set)
+        let take_profit = if trade.tp != crate::types::U192::ZERO {
+            Some(crate::constants::unscale_from_decimals(
+                U256::from(trade.tp),
+                crate::constants::PRICE_DECIMALS,
+            ))
+        } else {
+            None
+        };
+
+        let stop_loss = if trade.sl != crate::types::U192::ZERO {
+            Some(crate::constants::unscale_from_decimals(
+                U256::from(trade.sl),
+                crate::constants::PRICE_DECIMALS,
+            ))
+        } else {
+            None
+        };
+
+        let position = Position {
+            trader: trade.trader,
+            pair_index: trade.pairIndex,
             trade_index: trade.index,
             collateral,
             leverage,
@@ -344,7 +2095,59 @@ impl<S: TransactionSigner> OstiumClient<S> {
             take_profit,
             stop_loss,
             unrealized_pnl: None, // PnL requires current price, not available from contract
-        }))
+            trade_id: U256::from(info.tradeId),
+            opened_at: info.lastTradeTs as u64,
+            opened_block: info.lastTradeBlock as u64,
+            source: PositionSource::Contract,
+        };
+
+        // Reject corrupt reads rather than feeding garbage into PnL/risk math
+        position.validate().with_context(|| {
+            format!(
+                "Invalid position {}/{} for {}",
+                pair_index, trade_index, trader
+            )
+        })?;
+
+        Ok(Some(position))
+    }
+
+    /// Get one of the signer's positions, erroring if the slot is empty
+    async fn get_open_position(&self, pair_index: u16, trade_index: u8) -> Result<Position> {
+        self.get_position(self.address(), pair_index, trade_index)
+            .await?
+            .ok_or_else(|| {
+                eyre::eyre!("No open trade at pair {} index {}", pair_index, trade_index)
+            })
+    }
+
+    /// Get on-chain metadata for an open trade (trade ID, OI notional, last trade block/timestamp)
+    pub async fn get_trade_info(
+        &self,
+        trader: Address,
+        pair_index: u16,
+        trade_index: u8,
+    ) -> Result<TradeInfo> {
+        let call = ITradingStorage::getOpenTradeInfoCall {
+            trader,
+            pairIndex: pair_index,
+            index: trade_index,
+        };
+
+        let result: Bytes = self
+            .provider
+            .call(
+                alloy::rpc::types::TransactionRequest::default()
+                    .with_to(self.config.trading_storage)
+                    .with_input(call.abi_encode()),
+            )
+            .await
+            .context("Failed to get open trade info")?;
+
+        let info = ITradingStorage::getOpenTradeInfoCall::abi_decode_returns(&result)
+            .context("Failed to decode open trade info")?;
+
+        Ok(info)
     }
 
     // ========== Vault Operations ==========
@@ -359,55 +2162,187 @@ impl<S: TransactionSigner> OstiumClient<S> {
     ///
     /// Transaction hash of the deposit
     pub async fn deposit_olp(&self, params: DepositParams) -> Result<TxHash> {
-        let vault = self
-            .config
-            .vault
-            .ok_or_else(|| eyre::eyre!("Vault address not configured"))?;
+        let vault = self.vault()?;
 
-        let amount = params.scaled_amount();
-        let receiver = params.receiver.unwrap_or_else(|| self.address());
+        params.validate()?;
+        let amount = match params.balance_percent {
+            Some(_) => {
+                let balance = self.get_token_balance(self.config.usdc).await?;
+                let amount = params.resolve_amount(balance);
+                eyre::ensure!(!amount.is_zero(), "No USDC balance to deposit");
+                amount
+            }
+            None => params.scaled_amount(),
+        };
 
         // Ensure USDC allowance to vault
         self.ensure_usdc_allowance(vault, amount).await?;
 
         // Encode deposit call
-        let call = IOstiumVault::depositCall {
-            assets: amount,
-            receiver,
-        };
-        let data = Bytes::from(call.abi_encode());
+        let data = params.encode_calldata_for(amount, self.address());
 
         let tx = TxRequest::new(vault, data);
-        self.signer
-            .sign_and_send(tx)
+        self.send(tx).await.context("Failed to deposit to vault")
+    }
+
+    /// Deposit USDC to the OLP vault, returning a handle to await it
+    ///
+    /// See [`Self::deposit_olp`].
+    pub async fn deposit_olp_pending(
+        &self,
+        params: DepositParams,
+    ) -> Result<PendingTransaction<'_, S>> {
+        let hash = self.deposit_olp(params).await?;
+        Ok(self.pending_transaction(hash))
+    }
+
+    /// Deposit USDC to the OLP vault, approving it first if needed
+    ///
+    /// Unlike [`Self::deposit_olp`], the approval is explicit: its hash is
+    /// returned along with the deposit's, and `approval` chooses between
+    /// approving the exact amount and an unlimited allowance.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - USDC amount to deposit
+    /// * `approval` - How much to approve if the current allowance is too low
+    ///
+    /// # Returns
+    ///
+    /// Hashes of the approval transactions sent (if any), followed by the deposit's
+    pub async fn approve_and_deposit(
+        &self,
+        amount: f64,
+        approval: ApprovalMode,
+    ) -> Result<Vec<TxHash>> {
+        let vault = self.vault()?;
+        let params = DepositParams::new(amount);
+        params.validate()?;
+
+        let assets = params.scaled_amount();
+        let target = match approval {
+            ApprovalMode::Exact => assets,
+            ApprovalMode::Max => U256::MAX,
+        };
+        let mut tx_hashes = self
+            .raise_allowance(self.config.usdc, vault, assets, target)
             .await
-            .context("Failed to deposit to vault")
+            .context("Failed to approve USDC for the vault")?;
+
+        let tx = TxRequest::new(vault, params.encode_calldata(self.address()));
+        let deposit = self.send(tx).await.context("Failed to deposit to vault")?;
+        tx_hashes.push(deposit);
+        Ok(tx_hashes)
+    }
+
+    /// Get how much of the OLP vault's assets back open interest
+    ///
+    /// Reads the vault's `totalAssets` on-chain and sums long and short open
+    /// interest over all pairs from the subgraph, which can lag the chain
+    /// slightly. Each pair's open interest is in units of its base asset, so
+    /// it is valued in USDC at the current mid price before summing.
+    pub async fn get_vault_utilization(&self) -> Result<VaultUtilization> {
+        let vault = self.vault()?;
+        let subgraph = self.subgraph()?;
+
+        let (total_assets, pairs, prices) = tokio::try_join!(
+            async {
+                self.view_call(vault, IOstiumVault::totalAssetsCall {})
+                    .await
+                    .context("Failed to get vault total assets")
+            },
+            subgraph.get_pairs(),
+            get_all_prices_with_http_config(&self.config.http),
+        )?;
+
+        let (mut long_oi, mut short_oi) = (0.0, 0.0);
+        for pair in &pairs {
+            if parse_scaled(&pair.long_oi, 18)? == 0.0 && parse_scaled(&pair.short_oi, 18)? == 0.0 {
+                continue;
+            }
+            let price = prices
+                .iter()
+                .find(|price| price.from == pair.from && price.to == pair.to)
+                .ok_or_else(|| eyre::eyre!("No price found for {}/{}", pair.from, pair.to))?;
+            let info = PairInfo::from_parts(pair, price)?;
+            long_oi += info.oi_notional(true);
+            short_oi += info.oi_notional(false);
+        }
+
+        Ok(VaultUtilization {
+            total_assets: crate::constants::unscale_from_decimals(
+                total_assets,
+                crate::constants::USDC_DECIMALS,
+            ),
+            long_oi,
+            short_oi,
+        })
     }
 
     /// Get OLP share balance
     pub async fn get_olp_balance(&self) -> Result<VaultPosition> {
-        let vault = self
-            .config
-            .vault
-            .ok_or_else(|| eyre::eyre!("Vault address not configured"))?;
+        self.get_olp_balance_of(self.address()).await
+    }
 
-        // Get share balance
-        let balance_call = IOstiumVault::balanceOfCall {
-            account: self.address(),
-        };
-        let balance_result: Bytes = self
-            .provider
-            .call(
-                alloy::rpc::types::TransactionRequest::default()
-                    .with_to(vault)
-                    .with_input(balance_call.abi_encode()),
-            )
-            .await
-            .context("Failed to get OLP balance")?;
+    /// Get the OLP vault position of any address
+    pub async fn get_olp_balance_of(&self, account: Address) -> Result<VaultPosition> {
+        let vault = self.vault()?;
 
-        let shares = IOstiumVault::balanceOfCall::abi_decode_returns(&balance_result)?;
+        // Get share balance and the share token's decimals
+        let (shares, token) = tokio::try_join!(
+            async {
+                self.view_call(vault, IOstiumVault::balanceOfCall { account })
+                    .await
+                    .context("Failed to get OLP balance")
+            },
+            self.get_olp_token_info(),
+        )?;
 
         // Convert shares to assets
+        let assets = self.convert_to_assets(vault, shares).await?;
+
+        Ok(VaultPosition::new(shares, assets).with_share_decimals(token.decimals))
+    }
+
+    /// Get the OLP share token's name, symbol, and decimals
+    ///
+    /// Read from the vault on first use and cached until [`Self::refresh`].
+    pub async fn get_olp_token_info(&self) -> Result<TokenMetadata> {
+        let vault = self.vault()?;
+        self.olp_metadata
+            .get_or_try_insert_with((), || async {
+                let (name, symbol, decimals) = tokio::try_join!(
+                    self.view_call(vault, IOstiumVault::nameCall {}),
+                    self.view_call(vault, IOstiumVault::symbolCall {}),
+                    self.view_call(vault, IOstiumVault::decimalsCall {}),
+                )
+                .context("Failed to get OLP token metadata")?;
+
+                Ok(TokenMetadata {
+                    name,
+                    symbol,
+                    decimals,
+                })
+            })
+            .await
+    }
+
+    /// Convert several OLP share amounts to USDC asset values (raw, 6 decimals)
+    ///
+    /// The conversions run concurrently and are returned in input order.
+    pub async fn convert_many_to_assets(&self, shares: &[U256]) -> Result<Vec<U256>> {
+        let vault = self.vault()?;
+
+        futures::future::try_join_all(
+            shares
+                .iter()
+                .map(|&shares| self.convert_to_assets(vault, shares)),
+        )
+        .await
+    }
+
+    /// Convert OLP shares to their USDC asset value (raw, 6 decimals)
+    async fn convert_to_assets(&self, vault: Address, shares: U256) -> Result<U256> {
         let convert_call = IOstiumVault::convertToAssetsCall { shares };
         let convert_result: Bytes = self
             .provider
@@ -420,8 +2355,7 @@ impl<S: TransactionSigner> OstiumClient<S> {
             .context("Failed to convert shares to assets")?;
 
         let assets = IOstiumVault::convertToAssetsCall::abi_decode_returns(&convert_result)?;
-
-        Ok(VaultPosition::new(shares, assets))
+        Ok(assets)
     }
 
     /// Initialize a withdrawal request for OLP shares
@@ -431,16 +2365,14 @@ impl<S: TransactionSigner> OstiumClient<S> {
     ///
     /// # Arguments
     ///
-    /// * `shares` - Amount of OLP shares to withdraw (raw value with 6 decimals)
+    /// * `shares` - Amount of OLP shares to withdraw (raw value in the OLP token's decimals)
     ///
     /// # Returns
     ///
     /// Transaction hash of the withdrawal request
     pub async fn request_olp_withdrawal(&self, shares: U256) -> Result<TxHash> {
-        let vault = self
-            .config
-            .vault
-            .ok_or_else(|| eyre::eyre!("Vault address not configured"))?;
+        let vault = self.vault()?;
+        eyre::ensure!(!shares.is_zero(), "Withdrawal shares must be positive");
 
         let call = IOstiumVault::makeWithdrawRequestCall {
             shares,
@@ -449,46 +2381,138 @@ impl<S: TransactionSigner> OstiumClient<S> {
         let data = Bytes::from(call.abi_encode());
 
         let tx = TxRequest::new(vault, data);
-        self.signer
-            .sign_and_send(tx)
-            .await
-            .context("Failed to request withdrawal")
+        self.send(tx).await.context("Failed to request withdrawal")
     }
 
-    /// Get current vault epoch information
-    pub async fn get_vault_epoch(&self) -> Result<VaultEpoch> {
-        let vault = self
-            .config
-            .vault
-            .ok_or_else(|| eyre::eyre!("Vault address not configured"))?;
+    /// Quote the USDC a redemption of OLP shares would pay out right now
+    ///
+    /// Uses the vault's `previewRedeem`, which includes any withdrawal fees.
+    ///
+    /// # Arguments
+    ///
+    /// * `shares` - Amount of OLP shares (raw value in the OLP token's decimals)
+    pub async fn quote_withdrawal(&self, shares: U256) -> Result<f64> {
+        let vault = self.vault()?;
 
-        // Get current epoch
-        let epoch_call = IOstiumVault::currentEpochCall {};
-        let epoch_result: Bytes = self
-            .provider
-            .call(
-                alloy::rpc::types::TransactionRequest::default()
-                    .with_to(vault)
-                    .with_input(epoch_call.abi_encode()),
-            )
+        let assets = self
+            .view_call(vault, IOstiumVault::previewRedeemCall { shares })
             .await
-            .context("Failed to get current epoch")?;
-        let current_epoch = IOstiumVault::currentEpochCall::abi_decode_returns(&epoch_result)?;
+            .context("Failed to quote withdrawal")?;
 
-        // Get epoch start timestamp
-        let start_call = IOstiumVault::currentEpochStartCall {};
-        let start_result: Bytes = self
-            .provider
-            .call(
-                alloy::rpc::types::TransactionRequest::default()
-                    .with_to(vault)
-                    .with_input(start_call.abi_encode()),
+        Ok(crate::constants::unscale_from_decimals(
+            assets,
+            crate::constants::USDC_DECIMALS,
+        ))
+    }
+
+    /// Redeem OLP shares for USDC
+    ///
+    /// Only succeeds for shares whose withdrawal request has matured and whose
+    /// withdrawal window is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `shares` - Amount of OLP shares to redeem (raw value in the OLP token's decimals)
+    /// * `receiver` - Recipient of the USDC. Defaults to the signer's address.
+    ///
+    /// Fails with [`OstiumError::InsufficientShares`] before sending if
+    /// `shares` exceeds the signer's balance.
+    pub async fn redeem_olp(&self, shares: U256, receiver: Option<Address>) -> Result<TxHash> {
+        let params = RedeemParams {
+            receiver,
+            ..RedeemParams::new(shares)
+        };
+        self.redeem_olp_with(params).await
+    }
+
+    /// Redeem OLP shares for USDC, optionally capped to the signer's balance
+    ///
+    /// See [`Self::redeem_olp`]; with [`RedeemParams::clamp_to_balance`] an
+    /// overstated share count redeems the whole balance instead of failing.
+    pub async fn redeem_olp_with(&self, params: RedeemParams) -> Result<TxHash> {
+        let vault = self.vault()?;
+        params.validate()?;
+        let balance = self
+            .view_call(
+                vault,
+                IOstiumVault::balanceOfCall {
+                    account: self.address(),
+                },
             )
             .await
-            .context("Failed to get epoch start")?;
-        let epoch_start: u64 = IOstiumVault::currentEpochStartCall::abi_decode_returns(&start_result)?
-            .try_into()
-            .unwrap_or(0);
+            .context("Failed to get OLP share balance")?;
+        let shares = params.resolve_shares(balance)?;
+        self.send_redeem(vault, shares, params.receiver).await
+    }
+
+    /// Send the vault `redeem` call for the signer's shares
+    async fn send_redeem(
+        &self,
+        vault: Address,
+        shares: U256,
+        receiver: Option<Address>,
+    ) -> Result<TxHash> {
+        let call = IOstiumVault::redeemCall {
+            shares,
+            receiver: receiver.unwrap_or_else(|| self.address()),
+            owner: self.address(),
+        };
+
+        let tx = TxRequest::new(vault, Bytes::from(call.abi_encode()));
+        self.send(tx).await.context("Failed to redeem OLP")
+    }
+
+    /// Redeem all of the signer's currently redeemable OLP shares
+    ///
+    /// Redeems the smaller of the share balance and the vault's `maxRedeem`,
+    /// and errors instead of sending a transaction if nothing is redeemable.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` - Recipient of the USDC. Defaults to the signer's address.
+    pub async fn redeem_all_olp(&self, receiver: Option<Address>) -> Result<TxHash> {
+        let vault = self.vault()?;
+        let owner = self.address();
+
+        let (balance, max_redeem) = tokio::try_join!(
+            self.view_call(vault, IOstiumVault::balanceOfCall { account: owner }),
+            self.view_call(vault, IOstiumVault::maxRedeemCall { owner }),
+        )
+        .context("Failed to get redeemable OLP shares")?;
+
+        if balance.is_zero() {
+            eyre::bail!("No OLP shares to redeem");
+        }
+        let shares = balance.min(max_redeem);
+        if shares.is_zero() {
+            eyre::bail!(
+                "OLP shares are not redeemable yet; request a withdrawal and wait for its window"
+            );
+        }
+
+        self.send_redeem(vault, shares, receiver).await
+    }
+
+    /// Get current vault epoch information
+    ///
+    /// The epoch number and start are read concurrently as two `eth_call`s;
+    /// batching them into one call is deferred until multicall support lands.
+    pub async fn get_vault_epoch(&self) -> Result<VaultEpoch> {
+        let vault = self.vault()?;
+
+        let (current_epoch, epoch_start) = tokio::try_join!(
+            async {
+                self.view_call(vault, IOstiumVault::currentEpochCall {})
+                    .await
+                    .context("Failed to get current epoch")
+            },
+            async {
+                self.view_call(vault, IOstiumVault::currentEpochStartCall {})
+                    .await
+                    .context("Failed to get epoch start")
+            },
+        )?;
+        let epoch_start: u64 = epoch_start.try_into().unwrap_or(0);
 
         // Calculate epoch end (each epoch is 72 hours = 259200 seconds)
         const EPOCH_DURATION: u64 = 72 * 60 * 60; // 72 hours in seconds
@@ -510,6 +2534,116 @@ impl<S: TransactionSigner> OstiumClient<S> {
         })
     }
 
+    /// Watch for vault epoch rollovers and withdrawal window changes
+    ///
+    /// Polls `get_vault_epoch` every `poll_interval` and yields the first epoch
+    /// observed, then every epoch whose `current_epoch` or `withdrawals_open`
+    /// differs from the last one yielded. Read errors are logged and retried.
+    pub fn watch_epoch_rollover(
+        &self,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = VaultEpoch> + '_ {
+        futures::stream::unfold(None, move |last: Option<(u64, bool)>| async move {
+            loop {
+                match self.get_vault_epoch().await {
+                    Ok(epoch) => {
+                        let key = (epoch.current_epoch, epoch.withdrawals_open);
+                        if last != Some(key) {
+                            return Some((epoch, Some(key)));
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to read vault epoch: {:#}", e),
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        })
+    }
+
+    /// Wait for a requested withdrawal's window to open, then redeem the shares
+    ///
+    /// Finds the earliest epoch (current to current + 3) with a pending
+    /// request from the signer, polls the vault epoch every minute until that
+    /// epoch is reached with withdrawals open, and redeems `shares` through
+    /// [`Self::redeem_olp`]. Errors without sending anything if there is no
+    /// pending request or `timeout` elapses first.
+    ///
+    /// # Arguments
+    ///
+    /// * `shares` - Amount of OLP shares to redeem (raw value in the OLP token's decimals)
+    /// * `receiver` - Recipient of the USDC. Defaults to the signer's address.
+    /// * `timeout` - Longest time to wait for the window
+    pub async fn withdraw_when_open(
+        &self,
+        shares: U256,
+        receiver: Option<Address>,
+        timeout: Duration,
+    ) -> Result<TxHash> {
+        let current = self.get_vault_epoch().await?.current_epoch as u16;
+        let epochs: Vec<u16> = (current..=current.saturating_add(3)).collect();
+        let pending = futures::future::try_join_all(
+            epochs
+                .iter()
+                .map(|&epoch| self.get_pending_withdrawal(epoch)),
+        )
+        .await?;
+        let target = epochs
+            .iter()
+            .zip(&pending)
+            .find(|(_, shares)| !shares.is_zero())
+            .map(|(epoch, _)| *epoch as u64)
+            .ok_or_else(|| {
+                eyre::eyre!("No pending withdrawal request; call request_olp_withdrawal first")
+            })?;
+
+        let window = async {
+            loop {
+                match self.get_vault_epoch().await {
+                    Ok(epoch) if epoch.current_epoch >= target && epoch.withdrawals_open => break,
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to read vault epoch: {:#}", e),
+                }
+                tokio::time::sleep(WITHDRAWAL_POLL_INTERVAL).await;
+            }
+        };
+        tokio::time::timeout(timeout, window).await.map_err(|_| {
+            eyre::eyre!(
+                "Withdrawal window for epoch {} did not open within {:?}",
+                target,
+                timeout
+            )
+        })?;
+
+        self.redeem_olp(shares, receiver).await
+    }
+
+    /// Get the USDC the signer can withdraw from the OLP vault right now
+    ///
+    /// Zero while the withdrawal window is closed. Otherwise the value of the
+    /// shares whose withdrawal request matured into the current epoch, capped
+    /// by the vault's `maxWithdraw` for the signer.
+    pub async fn get_withdrawable_now(&self) -> Result<f64> {
+        let vault = self.vault()?;
+        let epoch = self.get_vault_epoch().await?;
+        if !epoch.withdrawals_open {
+            return Ok(0.0);
+        }
+
+        let (requested, max_withdraw) = tokio::try_join!(
+            self.get_pending_withdrawal(epoch.current_epoch as u16),
+            self.view_call(
+                vault,
+                IOstiumVault::maxWithdrawCall {
+                    owner: self.address(),
+                },
+            ),
+        )?;
+        let requested_assets = self.convert_to_assets(vault, requested).await?;
+        Ok(unscale_from_decimals(
+            requested_assets.min(max_withdraw),
+            USDC_DECIMALS,
+        ))
+    }
+
     /// Get pending withdrawal request for the current user
     ///
     /// # Arguments
@@ -520,10 +2654,7 @@ impl<S: TransactionSigner> OstiumClient<S> {
     ///
     /// Amount of shares pending withdrawal for the given epoch
     pub async fn get_pending_withdrawal(&self, epoch: u16) -> Result<U256> {
-        let vault = self
-            .config
-            .vault
-            .ok_or_else(|| eyre::eyre!("Vault address not configured"))?;
+        let vault = self.vault()?;
 
         let call = IOstiumVault::withdrawRequestsCall {
             owner: self.address(),
@@ -552,16 +2683,13 @@ impl<S: TransactionSigner> OstiumClient<S> {
     ///
     /// # Arguments
     ///
-    /// * `shares` - Amount of OLP shares to approve (raw value with 6 decimals)
+    /// * `shares` - Amount of OLP shares to approve (raw value in the OLP token's decimals)
     ///
     /// # Returns
     ///
     /// Transaction hash of the approval
     pub async fn approve_auto_withdraw(&self, shares: U256) -> Result<TxHash> {
-        let vault = self
-            .config
-            .vault
-            .ok_or_else(|| eyre::eyre!("Vault address not configured"))?;
+        let vault = self.vault()?;
 
         let auto_withdraw = self
             .config
@@ -574,10 +2702,7 @@ impl<S: TransactionSigner> OstiumClient<S> {
 
     /// Get current OLP allowance for the auto-withdraw contract
     pub async fn get_auto_withdraw_allowance(&self) -> Result<U256> {
-        let vault = self
-            .config
-            .vault
-            .ok_or_else(|| eyre::eyre!("Vault address not configured"))?;
+        let vault = self.vault()?;
 
         let auto_withdraw = self
             .config
@@ -606,11 +2731,364 @@ impl<S: TransactionSigner> OstiumClient<S> {
         Ok(decoded)
     }
 
+    // ========== Account Overview ==========
+
+    /// Get a trader's realized, unrealized and net PnL
+    ///
+    /// Realized PnL comes from the subgraph's close, TP/SL and liquidation
+    /// orders executed at or after `since` (Unix seconds; `None` for all time,
+    /// up to the latest 1000 closes). Unrealized PnL values the open positions
+    /// at the current mid price, fetching all prices in one request. Requires
+    /// a configured subgraph.
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    /// * `since` - Start of the realized PnL timeframe
+    pub async fn get_total_pnl(
+        &self,
+        trader: Option<Address>,
+        since: Option<u64>,
+    ) -> Result<PnlBreakdown> {
+        let trader = trader.unwrap_or_else(|| self.address());
+        let subgraph = self.subgraph()?;
+        let (orders, positions, pairs) = tokio::try_join!(
+            subgraph.get_closed_orders(trader, since.unwrap_or(0)),
+            self.get_positions(Some(trader)),
+            subgraph.get_pairs(),
+        )?;
+
+        let quotes = self.position_quotes(&positions, &pairs).await?;
+        let unrealized = positions
+            .iter()
+            .zip(quotes)
+            .map(|(position, (_, quote))| position.pnl_at(quote.mid))
+            .sum();
+        Ok(PnlBreakdown::new(realized_pnl(&orders)?, unrealized))
+    }
+
+    /// Get open positions whose health is below `health_threshold`, worst first
+    ///
+    /// Values every position at the price it would close at (bid for longs,
+    /// ask for shorts), fetching all prices in one request, and pairs it with
+    /// its [`Position::health`] score (1.0 at zero PnL, 0.0 at liquidation). A
+    /// threshold around 0.3 flags positions that have lost most of their
+    /// buffer. Requires a configured subgraph.
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - Optional address to query. Defaults to the signer's address.
+    /// * `health_threshold` - Return positions with health strictly below this
+    pub async fn get_positions_at_risk(
+        &self,
+        trader: Option<Address>,
+        health_threshold: f64,
+    ) -> Result<Vec<(Position, f64)>> {
+        let (positions, pairs) =
+            tokio::try_join!(self.get_positions(trader), self.subgraph()?.get_pairs())?;
+        let quotes = self.position_quotes(&positions, &pairs).await?;
+
+        let mut at_risk: Vec<(Position, f64)> = positions
+            .into_iter()
+            .zip(quotes)
+            .map(|(position, (_, quote))| {
+                let health = position.health(close_price(&position, &quote));
+                (position, health)
+            })
+            .filter(|(_, health)| *health < health_threshold)
+            .collect();
+        at_risk.sort_by(|a, b| a.1.total_cmp(&b.1));
+        Ok(at_risk)
+    }
+
+    /// Subgraph pair and current quote of each position's pair, in position order
+    async fn position_quotes<'a>(
+        &self,
+        positions: &[Position],
+        pairs: &'a [SubgraphPair],
+    ) -> Result<Vec<(&'a SubgraphPair, PriceData)>> {
+        if positions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let prices = get_all_prices_with_http_config(&self.config.http).await?;
+        positions
+            .iter()
+            .map(|position| {
+                let pair_id = position.pair_index.to_string();
+                let pair = pairs
+                    .iter()
+                    .find(|pair| pair.id == pair_id)
+                    .ok_or_else(|| eyre::eyre!("Pair {} not found in subgraph", pair_id))?;
+                let quote = prices
+                    .iter()
+                    .find(|price| price.from == pair.from && price.to == pair.to)
+                    .cloned()
+                    .ok_or_else(|| eyre::eyre!("No price found for {}/{}", pair.from, pair.to))?;
+                Ok((pair, quote))
+            })
+            .collect()
+    }
+
+    /// Get total account value with a per-component breakdown
+    ///
+    /// Sums free USDC, the net value of open positions (collateral + unrealized
+    /// PnL at the price each would close at - estimated close fee), and the OLP
+    /// vault value. Positions are priced like [`Self::get_positions_at_risk`]:
+    /// bid for longs, ask for shorts. Shares with pending withdrawal requests
+    /// are still held, so they are reported separately but already included
+    /// in the OLP value.
+    pub async fn get_account_value(&self) -> Result<AccountValue> {
+        let subgraph = self.subgraph()?;
+        let (free_usdc, positions, pairs) = tokio::try_join!(
+            self.get_usdc_balance(),
+            self.get_positions(None),
+            subgraph.get_pairs(),
+        )?;
+
+        let quotes = self.position_quotes(&positions, &pairs).await?;
+        let mut positions_value = 0.0;
+        for (position, (pair, quote)) in positions.iter().zip(&quotes) {
+            let info = PairInfo::from_parts(pair, quote)?;
+            let close_fee = position.notional() * info.taker_fee_percent / 100.0;
+            positions_value +=
+                position.collateral + position.pnl_at(close_price(position, quote)) - close_fee;
+        }
+
+        let (olp_value, pending_withdrawals_value) = match self.config.vault {
+            Some(vault) => {
+                let (olp, epoch) =
+                    tokio::try_join!(self.get_olp_balance(), self.get_vault_epoch())?;
+
+                // Requests mature 1-3 epochs after they are made
+                let current = epoch.current_epoch as u16;
+                let pending = futures::future::try_join_all(
+                    (current..=current.saturating_add(3)).map(|e| self.get_pending_withdrawal(e)),
+                )
+                .await?;
+                let pending_shares = pending.into_iter().fold(U256::ZERO, |acc, s| acc + s);
+                let pending_assets = if pending_shares.is_zero() {
+                    U256::ZERO
+                } else {
+                    self.convert_to_assets(vault, pending_shares).await?
+                };
+
+                (
+                    olp.value,
+                    crate::constants::unscale_from_decimals(
+                        pending_assets,
+                        crate::constants::USDC_DECIMALS,
+                    ),
+                )
+            }
+            None => (0.0, 0.0),
+        };
+
+        Ok(AccountValue::new(
+            free_usdc,
+            positions_value,
+            olp_value,
+            pending_withdrawals_value,
+        ))
+    }
+
     // ========== Utility Methods ==========
 
+    /// Check that the RPC, price API, and subgraph are reachable
+    ///
+    /// Probes all three concurrently (a block number read, a latest-prices
+    /// fetch, and a subgraph `_meta` query) and reports each one's latency and
+    /// error. Failed probes are reported rather than returned as errors, so a
+    /// single call shows every unhealthy dependency before a trading session.
+    pub async fn connectivity_check(&self) -> Result<ConnectivityReport> {
+        let rpc = timed(async {
+            self.provider
+                .get_block_number()
+                .await
+                .context("Failed to get block number")
+        });
+        let prices = timed(get_all_prices_with_http_config(&self.config.http));
+        let subgraph = async {
+            match &self.subgraph {
+                Some(subgraph) => Some(timed(subgraph.indexed_block()).await.0),
+                None => None,
+            }
+        };
+        let ((rpc, block_number), (price_api, _), subgraph) = tokio::join!(rpc, prices, subgraph);
+
+        Ok(ConnectivityReport {
+            rpc,
+            block_number,
+            price_api,
+            subgraph,
+        })
+    }
+
+    /// Estimate gas for a transaction from the signer and add a safety margin
+    ///
+    /// Ostium's oracle-callback trades are occasionally underestimated by
+    /// signer-side estimation, so writes set an explicit gas limit computed
+    /// here with the client's configured buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Transaction to estimate
+    /// * `buffer_percent` - Percentage added on top of the estimate (e.g. 20)
+    pub async fn estimate_gas_with_buffer(
+        &self,
+        tx: &TxRequest,
+        buffer_percent: u64,
+    ) -> Result<u64> {
+        let request = alloy::rpc::types::TransactionRequest::default()
+            .with_from(self.address())
+            .with_to(tx.to)
+            .with_value(tx.value)
+            .with_input(tx.data.clone());
+
+        let estimate = self
+            .provider
+            .estimate_gas(request)
+            .await
+            .context("Failed to estimate gas")?;
+
+        Ok(apply_gas_buffer(estimate, buffer_percent))
+    }
+
+    /// Send a write, setting a buffered gas limit unless one is already set
+    ///
+    /// The client's fixed gas limit, if configured, is used as is. Otherwise,
+    /// if estimation fails (e.g. an approval from the same flow is not mined
+    /// yet), the transaction is sent without a limit and the signer estimates.
+    async fn send(&self, mut tx: TxRequest) -> Result<TxHash> {
+        if let (None, Some(gas_limit)) = (tx.gas_limit, self.gas_limit) {
+            tx = tx.with_gas_limit(gas_limit);
+        }
+        if tx.gas_limit.is_none() {
+            match self
+                .estimate_gas_with_buffer(&tx, self.gas_buffer_percent)
+                .await
+            {
+                Ok(gas_limit) => tx = tx.with_gas_limit(gas_limit),
+                Err(e) => {
+                    tracing::warn!("Gas estimation failed, leaving it to the signer: {:#}", e)
+                }
+            }
+        }
+        self.signer.sign_and_send(tx).await
+    }
+
+    /// Wrap a transaction hash in a handle that can be awaited
+    pub fn pending_transaction(&self, hash: TxHash) -> PendingTransaction<'_, S> {
+        PendingTransaction { client: self, hash }
+    }
+
     /// Wait for transaction confirmation
     pub async fn wait_for_receipt(&self, tx_hash: TxHash) -> Result<TransactionReceipt> {
-        self.signer.wait_for_receipt(tx_hash).await
+        match &self.poll_config {
+            Some(poll) => self.signer.wait_for_receipt_with(tx_hash, poll).await,
+            None => self.signer.wait_for_receipt(tx_hash).await,
+        }
+    }
+
+    /// Wait for whichever of several competing transactions confirms first
+    ///
+    /// Meant for replacement/speed-up flows where an original and its
+    /// replacement race for the same nonce and only one can be mined. All
+    /// hashes are polled concurrently each round at the client's poll interval.
+    ///
+    /// # Returns
+    ///
+    /// The winning hash and its receipt; a timeout is tagged
+    /// [`OstiumError::RetryUnsafe`] since any of the hashes may still be mined
+    pub async fn wait_for_any_receipt(
+        &self,
+        hashes: &[TxHash],
+        timeout: Duration,
+    ) -> Result<(TxHash, TransactionReceipt)> {
+        if hashes.is_empty() {
+            eyre::bail!("No transaction hashes to wait for");
+        }
+
+        let interval = self.poll_config.unwrap_or_default().interval;
+        let poll = async {
+            loop {
+                let receipts = futures::future::join_all(
+                    hashes
+                        .iter()
+                        .map(|hash| self.provider.get_transaction_receipt(*hash)),
+                )
+                .await;
+
+                for (hash, receipt) in hashes.iter().zip(receipts) {
+                    match receipt {
+                        Ok(Some(receipt)) => return (*hash, receipt),
+                        Ok(None) => {}
+                        Err(e) => tracing::debug!("Receipt lookup for {} failed: {}", hash, e),
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, poll).await.map_err(|_| {
+            Report::new(OstiumError::RetryUnsafe(format!(
+                "None of {} transactions confirmed within {:?}",
+                hashes.len(),
+                timeout
+            )))
+        })
+    }
+
+    /// Summarize a receipt: status, gas cost, and decoded Ostium events
+    ///
+    /// Decodes `PriceRequested` logs from the Trading contract and `Deposit`/
+    /// `Withdraw` logs from the configured OLP vault; other logs are ignored.
+    pub fn summarize_receipt(&self, receipt: &TransactionReceipt) -> ReceiptSummary {
+        let events = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter_map(|log| {
+                if log.address() == self.config.trading {
+                    decode_trade_event(log).map(OstiumEvent::Trade)
+                } else if Some(log.address()) == self.config.vault {
+                    decode_vault_event(log)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        ReceiptSummary {
+            tx_hash: receipt.transaction_hash,
+            success: receipt.status(),
+            block_number: receipt.block_number,
+            gas_used: receipt.gas_used,
+            effective_gas_price: receipt.effective_gas_price,
+            events,
+        }
+    }
+
+    /// Decode Trading, OLP vault or ERC-20 calldata into its function name and
+    /// arguments, with collateral, prices, leverage and slippage unscaled
+    ///
+    /// `delegatedAction` calls also decode the wrapped call into `inner`. OLP
+    /// share amounts are unscaled with the vault token's decimals, and are left
+    /// raw when no vault is configured or its metadata can't be read.
+    pub async fn decode_calldata(&self, data: &[u8]) -> Result<DecodedCall> {
+        let share_decimals = match self.config.vault {
+            Some(_) => match self.get_olp_token_info().await {
+                Ok(token) => Some(token.decimals),
+                Err(e) => {
+                    tracing::warn!(
+                        "Leaving OLP shares raw, token metadata unavailable: {:#}",
+                        e
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+        decode_call(data, share_decimals)
     }
 
     /// Get native token (ETH) balance
@@ -618,3 +3096,821 @@ impl<S: TransactionSigner> OstiumClient<S> {
         self.signer.get_balance().await
     }
 }
+
+/// A submitted transaction that can be awaited through its client
+///
+/// Returned by the `*_pending` write variants, so submitting and waiting read
+/// as one chain: `client.close_trade_pending(params).await?.await_receipt().await?`.
+pub struct PendingTransaction<'a, S: TransactionSigner> {
+    client: &'a OstiumClient<S>,
+    hash: TxHash,
+}
+
+impl<'a, S: TransactionSigner> PendingTransaction<'a, S> {
+    /// Transaction hash
+    pub fn hash(&self) -> TxHash {
+        self.hash
+    }
+
+    /// Wait for the receipt using the client's poll settings
+    pub async fn await_receipt(&self) -> Result<TransactionReceipt> {
+        self.client.wait_for_receipt(self.hash).await
+    }
+
+    /// Wait until the transaction has succeeded and has `confirmations` blocks
+    ///
+    /// The inclusion block counts as the first confirmation, so 0 and 1 both
+    /// return as soon as the receipt is available. Once the depth is reached
+    /// the receipt is read again; if a reorg moved the transaction, the count
+    /// restarts from its new block.
+    ///
+    /// # Errors
+    ///
+    /// A reverted transaction is [`OstiumError::TransactionReverted`]. Running
+    /// out of the client's `max_receipt_attempts` block polls is
+    /// [`OstiumError::RetryUnsafe`], since the transaction is already mined.
+    pub async fn await_confirmed(&self, confirmations: u64) -> Result<TransactionReceipt> {
+        let poll = self.client.poll_config.unwrap_or_default();
+        let mut receipt = self.await_receipt().await?;
+
+        for _ in 0..poll.max_receipt_attempts {
+            if !receipt.status() {
+                return Err(Report::new(OstiumError::TransactionReverted(
+                    self.hash.to_string(),
+                )));
+            }
+            let included = receipt
+                .block_number
+                .ok_or_else(|| eyre::eyre!("Receipt for {} has no block number", self.hash))?;
+            let target = included + confirmations.saturating_sub(1);
+
+            let head = self
+                .client
+                .provider
+                .get_block_number()
+                .await
+                .context("Failed to get block number")?;
+            if head >= target {
+                match self
+                    .client
+                    .provider
+                    .get_transaction_receipt(self.hash)
+                    .await
+                    .context("Failed to recheck receipt")?
+                {
+                    Some(current) if current.block_hash == receipt.block_hash => {
+                        return Ok(current)
+                    }
+                    Some(current) => receipt = current,
+                    // Dropped by a reorg; wait for it to be mined again
+                    None => receipt = self.await_receipt().await?,
+                }
+                continue;
+            }
+            tokio::time::sleep(poll.interval).await;
+        }
+
+        Err(Report::new(OstiumError::RetryUnsafe(format!(
+            "Transaction {} did not reach {} confirmations after {} polls",
+            self.hash, confirmations, poll.max_receipt_attempts
+        ))))
+    }
+}
+
+/// Decode a Trading contract log into a trade event
+fn decode_trade_event(log: &Log) -> Option<TradeEvent> {
+    let decoded = log.log_decode::<ITrading::PriceRequested>().ok()?;
+    let event = decoded.inner.data;
+    let request = PriceRequest {
+        order_id: event.orderId,
+        pair_index: event.pairIndex,
+        order_type: event.orderType,
+        timestamp: event.timestamp.try_into().unwrap_or(0),
+        tx_hash: log.transaction_hash,
+        block_number: log.block_number,
+    };
+
+    Some(if event.open {
+        TradeEvent::OpenRequested(request)
+    } else {
+        TradeEvent::CloseRequested(request)
+    })
+}
+
+/// Decode an OLP vault log into a deposit or withdrawal event
+fn decode_vault_event(log: &Log) -> Option<OstiumEvent> {
+    if let Ok(decoded) = log.log_decode::<IOstiumVault::Deposit>() {
+        let event = decoded.inner.data;
+        return Some(OstiumEvent::Deposit {
+            sender: event.sender,
+            owner: event.owner,
+            assets: event.assets,
+            shares: event.shares,
+        });
+    }
+
+    let event = log.log_decode::<IOstiumVault::Withdraw>().ok()?.inner.data;
+    Some(OstiumEvent::Withdraw {
+        sender: event.sender,
+        receiver: event.receiver,
+        owner: event.owner,
+        assets: event.assets,
+        shares: event.shares,
+    })
+}
+
+/// Decode Trading, OLP vault or ERC-20 calldata into a readable call
+///
+/// Trading is matched first, then ERC-20, then the vault, so the ERC-20
+/// functions the vault shares (e.g. `approve`) are reported as `IERC20`.
+fn decode_call(data: &[u8], share_decimals: Option<u8>) -> Result<DecodedCall> {
+    if data.len() < 4 {
+        return Err(Report::new(OstiumError::Decode(format!(
+            "Calldata too short for a selector: {} bytes",
+            data.len()
+        ))));
+    }
+    let (selector, input) = data.split_at(4);
+
+    let abis = [
+        ("ITrading", ITrading::abi::contract()),
+        ("IERC20", IERC20::abi::contract()),
+        ("IOstiumVault", IOstiumVault::abi::contract()),
+    ];
+    for (contract, abi) in &abis {
+        let Some(function) = abi
+            .functions()
+            .find(|function| function.selector().as_slice() == selector)
+        else {
+            continue;
+        };
+
+        let values = function.abi_decode_input(input).map_err(|e| {
+            Report::new(OstiumError::Decode(e.to_string()))
+                .wrap_err(format!("Failed to decode {}.{}", contract, function.name))
+        })?;
+
+        let mut args = Vec::new();
+        for (param, value) in function.inputs.iter().zip(&values) {
+            flatten_call_arg(
+                &param.name,
+                &param.components,
+                value,
+                share_decimals,
+                &mut args,
+            );
+        }
+
+        let inner = match (function.name.as_str(), values.get(1)) {
+            ("delegatedAction", Some(DynSolValue::Bytes(call_data))) => {
+                Some(Box::new(decode_call(call_data, share_decimals)?))
+            }
+            _ => None,
+        };
+
+        return Ok(DecodedCall {
+            contract: contract.to_string(),
+            function: function.name.clone(),
+            args,
+            inner,
+        });
+    }
+
+    Err(Report::new(OstiumError::Decode(format!(
+        "Unknown selector 0x{}",
+        alloy::hex::encode(selector)
+    ))))
+}
+
+/// Flatten a decoded argument into `(name, value)` pairs, expanding struct
+/// fields as `parent.field`
+fn flatten_call_arg(
+    name: &str,
+    components: &[Param],
+    value: &DynSolValue,
+    share_decimals: Option<u8>,
+    args: &mut Vec<(String, String)>,
+) {
+    if let DynSolValue::Tuple(fields) = value {
+        for (component, field) in components.iter().zip(fields) {
+            let field_name = format!("{}.{}", name, component.name);
+            flatten_call_arg(
+                &field_name,
+                &component.components,
+                field,
+                share_decimals,
+                args,
+            );
+        }
+        return;
+    }
+
+    let field = name.rsplit('.').next().unwrap_or(name);
+    let formatted = match value {
+        DynSolValue::Uint(raw, _) => format_call_amount(field, *raw, share_decimals),
+        DynSolValue::Address(address) => address.to_string(),
+        DynSolValue::Bool(flag) => flag.to_string(),
+        DynSolValue::Bytes(bytes) => format!("0x{}", alloy::hex::encode(bytes)),
+        other => format!("{:?}", other),
+    };
+    args.push((name.to_string(), formatted));
+}
+
+/// Unscale a raw integer argument based on its parameter name
+///
+/// USDC amounts use 6 decimals; OLP shares use `share_decimals` and stay raw
+/// when it is unknown.
+fn format_call_amount(field: &str, raw: U256, share_decimals: Option<u8>) -> String {
+    if raw == U256::MAX {
+        return "unlimited".to_string();
+    }
+    let decimals = match field {
+        "collateral" | "amount" | "assets" => USDC_DECIMALS,
+        "shares" => match share_decimals {
+            Some(decimals) => decimals,
+            None => return raw.to_string(),
+        },
+        "openPrice" | "tp" | "sl" | "marketPrice" | "newTp" | "newSl" => PRICE_DECIMALS,
+        "leverage" | "slippageP" | "closePercentage" => LEVERAGE_DECIMALS,
+        _ => return raw.to_string(),
+    };
+    // Format exactly rather than via f64 so 18-decimal prices don't pick up noise
+    match format_units(raw, decimals) {
+        Ok(formatted) => formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+        Err(_) => raw.to_string(),
+    }
+}
+
+/// Price a position would close at: the bid for longs, the ask for shorts
+fn close_price(position: &Position, quote: &PriceData) -> f64 {
+    if position.is_long {
+        quote.bid
+    } else {
+        quote.ask
+    }
+}
+
+/// Scale a gas estimate up by a percentage, saturating on overflow
+fn apply_gas_buffer(estimate: u64, buffer_percent: u64) -> u64 {
+    let buffered = u128::from(estimate) * u128::from(100 + buffer_percent) / 100;
+    u64::try_from(buffered).unwrap_or(u64::MAX)
+}
+
+/// Approval amounts needed to move an allowance from `current` to `target`
+fn approval_steps(current: U256, target: U256, reset_first: bool) -> Vec<U256> {
+    if current == target {
+        Vec::new()
+    } else if reset_first && !current.is_zero() && !target.is_zero() {
+        vec![U256::ZERO, target]
+    } else {
+        vec![target]
+    }
+}
+
+/// Run a connectivity probe, timing it and capturing its error
+async fn timed<T>(
+    probe: impl std::future::Future<Output = Result<T>>,
+) -> (EndpointStatus, Option<T>) {
+    let started = Instant::now();
+    let result = probe.await;
+    let latency = started.elapsed();
+    match result {
+        Ok(value) => (
+            EndpointStatus {
+                latency,
+                error: None,
+            },
+            Some(value),
+        ),
+        Err(e) => (
+            EndpointStatus {
+                latency,
+                error: Some(format!("{:#}", e)),
+            },
+            None,
+        ),
+    }
+}
+
+/// Group positions by pair index, sorted by pair and keeping each pair's order
+fn group_positions_by_pair(positions: Vec<Position>) -> Vec<(u16, Vec<Position>)> {
+    let mut groups: Vec<(u16, Vec<Position>)> = Vec::new();
+    for position in positions {
+        match groups
+            .iter_mut()
+            .find(|(pair_index, _)| *pair_index == position.pair_index)
+        {
+            Some((_, group)) => group.push(position),
+            None => groups.push((position.pair_index, vec![position])),
+        }
+    }
+    groups.sort_by_key(|(pair_index, _)| *pair_index);
+    groups
+}
+
+/// Drop positions whose trade ID was already seen, keeping the first
+///
+/// A read racing a close and reopen in the same block can return one trade at
+/// two indices. Positions without a trade ID (zero) are always kept.
+fn dedup_positions(positions: Vec<Position>) -> Vec<Position> {
+    let mut seen = HashSet::new();
+    positions
+        .into_iter()
+        .filter(|position| {
+            if position.trade_id.is_zero() || seen.insert(position.trade_id) {
+                return true;
+            }
+            tracing::warn!(
+                "Dropping duplicate of trade {} at pair {} index {}",
+                position.trade_id,
+                position.pair_index,
+                position.trade_index
+            );
+            false
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::trade::tests::test_position;
+    use alloy::transports::mock::Asserter;
+
+    /// Signer for clients built on a mocked provider
+    ///
+    /// Sends are refused unless `accept_sends` is set. Accepted sends and
+    /// receipt waits are recorded in `calls`, and waits hand out `receipt`.
+    #[derive(Default)]
+    struct MockSigner {
+        receipt: Option<TransactionReceipt>,
+        accept_sends: bool,
+        calls: std::sync::Mutex<Vec<MockCall>>,
+    }
+
+    /// A call made on a [`MockSigner`]
+    #[derive(Debug, Clone, PartialEq)]
+    enum MockCall {
+        Send { to: Address, data: Bytes },
+        Wait(TxHash),
+    }
+
+    impl TransactionSigner for MockSigner {
+        fn address(&self) -> Address {
+            Address::repeat_byte(0xaa)
+        }
+
+        async fn sign_and_send(&self, tx: TxRequest) -> Result<TxHash> {
+            eyre::ensure!(self.accept_sends, "MockSigner can't send");
+            let mut calls = self.calls.lock().unwrap();
+            calls.push(MockCall::Send {
+                to: tx.to,
+                data: tx.data,
+            });
+            Ok(TxHash::with_last_byte(calls.len() as u8))
+        }
+
+        async fn wait_for_receipt(&self, tx_hash: TxHash) -> Result<TransactionReceipt> {
+            self.calls.lock().unwrap().push(MockCall::Wait(tx_hash));
+            self.receipt
+                .clone()
+                .ok_or_else(|| eyre::eyre!("MockSigner has no receipt"))
+        }
+
+        async fn get_balance(&self) -> Result<U256> {
+            Ok(U256::ZERO)
+        }
+    }
+
+    /// Client whose RPC reads are answered by `asserter`, in order
+    fn mock_client(asserter: Asserter) -> OstiumClient<MockSigner> {
+        mock_client_with_signer(asserter, MockSigner::default())
+    }
+
+    fn mock_client_with_signer(asserter: Asserter, signer: MockSigner) -> OstiumClient<MockSigner> {
+        let provider = ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .network::<Ethereum>()
+            .connect_mocked_client(asserter);
+        OstiumClient::with_provider(signer, NetworkConfig::arbitrum_one_public(), provider).unwrap()
+    }
+
+    /// Receipt for a transaction mined in `block_number`
+    fn test_receipt(block_number: u64, success: bool) -> TransactionReceipt {
+        serde_json::from_value(serde_json::json!({
+            "transactionHash": TxHash::repeat_byte(1),
+            "transactionIndex": "0x0",
+            "blockHash": alloy::primitives::B256::with_last_byte(block_number as u8),
+            "blockNumber": format!("{:#x}", block_number),
+            "from": Address::repeat_byte(0xaa),
+            "to": Address::repeat_byte(0xbb),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": alloy::primitives::Bloom::ZERO,
+            "type": "0x2",
+            "status": if success { "0x1" } else { "0x0" },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_await_confirmed() {
+        let poll = PollConfig::default()
+            .with_interval(Duration::from_millis(1))
+            .with_max_receipt_attempts(3);
+        let hash = TxHash::repeat_byte(1);
+
+        // Waits for depth, then rechecks the receipt and follows a reorg to block 17
+        let asserter = Asserter::new();
+        asserter.push_success(&"0x11");
+        asserter.push_success(&test_receipt(17, true));
+        asserter.push_success(&"0x12");
+        asserter.push_success(&test_receipt(17, true));
+        let signer = MockSigner {
+            receipt: Some(test_receipt(16, true)),
+            ..Default::default()
+        };
+        let client = mock_client_with_signer(asserter, signer).with_poll_config(poll);
+        let receipt = client
+            .pending_transaction(hash)
+            .await_confirmed(2)
+            .await
+            .unwrap();
+        assert_eq!(receipt.block_number, Some(17));
+
+        let signer = MockSigner {
+            receipt: Some(test_receipt(16, false)),
+            ..Default::default()
+        };
+        let client = mock_client_with_signer(Asserter::new(), signer).with_poll_config(poll);
+        let err = client
+            .pending_transaction(hash)
+            .await_confirmed(2)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<OstiumError>(),
+            Some(OstiumError::TransactionReverted(_))
+        ));
+
+        // The chain never reaches the target depth within the poll budget
+        let asserter = Asserter::new();
+        for _ in 0..3 {
+            asserter.push_success(&"0x10");
+        }
+        let signer = MockSigner {
+            receipt: Some(test_receipt(16, true)),
+            ..Default::default()
+        };
+        let client = mock_client_with_signer(asserter, signer).with_poll_config(poll);
+        let err = client
+            .pending_transaction(hash)
+            .await_confirmed(3)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<OstiumError>(),
+            Some(OstiumError::RetryUnsafe(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_close_rejects_invalid_percentage_before_reads() {
+        // No RPC responses are queued, so any read would fail differently
+        let client = mock_client(Asserter::new());
+        let params = CloseTradeParams {
+            close_percentage: 150.0,
+            ..CloseTradeParams::close_all(0, 0, 50_000.0)
+        };
+        let is_rejected = |err: eyre::Report| err.to_string().contains("Close percentage");
+
+        assert!(is_rejected(
+            client.close_trade(params.clone()).await.unwrap_err()
+        ));
+        assert!(is_rejected(
+            client
+                .close_trade_confirmed(params.clone(), Duration::from_secs(1))
+                .await
+                .unwrap_err()
+        ));
+        assert!(is_rejected(
+            client.simulate_close(&params).await.unwrap_err()
+        ));
+    }
+
+    #[test]
+    fn test_positions_cache_skipped_while_fill_pending() {
+        let client = mock_client(Asserter::new()).with_positions_cache(Duration::from_secs(60));
+        let trader = client.address();
+        let other = Address::repeat_byte(2);
+
+        client.invalidate_own_positions();
+        assert!(client.positions_cache_for(trader).is_none());
+        assert!(client.positions_cache_for(other).is_some());
+
+        // Once the fill is observed, the trader is cached again
+        client.settle_cached_positions(trader);
+        assert!(client.positions_cache_for(trader).is_some());
+    }
+
+    #[test]
+    fn test_apply_gas_buffer() {
+        assert_eq!(
+            apply_gas_buffer(100_000, DEFAULT_GAS_BUFFER_PERCENT),
+            120_000
+        );
+        assert_eq!(apply_gas_buffer(100_000, 0), 100_000);
+        assert_eq!(apply_gas_buffer(u64::MAX, 50), u64::MAX);
+    }
+
+    #[test]
+    fn test_decode_vault_event() {
+        let deposit = IOstiumVault::Deposit {
+            sender: Address::repeat_byte(1),
+            owner: Address::repeat_byte(2),
+            assets: U256::from(10_000_000u64),
+            shares: U256::from(9_000_000u64),
+        };
+        let log = Log {
+            inner: alloy::primitives::Log {
+                address: Address::repeat_byte(3),
+                data: deposit.encode_log_data(),
+            },
+            ..Default::default()
+        };
+
+        match decode_vault_event(&log) {
+            Some(OstiumEvent::Deposit { owner, shares, .. }) => {
+                assert_eq!(owner, Address::repeat_byte(2));
+                assert_eq!(shares, U256::from(9_000_000u64));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(decode_trade_event(&log).is_none());
+    }
+
+    /// Token that rejects changing a non-zero allowance to another non-zero value
+    struct ResetRequiredToken {
+        allowance: U256,
+    }
+
+    impl ResetRequiredToken {
+        fn approve(&mut self, amount: U256) -> Result<()> {
+            eyre::ensure!(
+                self.allowance.is_zero() || amount.is_zero(),
+                "approve from non-zero allowance"
+            );
+            self.allowance = amount;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_allowance_reset_before_change() {
+        let (token, spender) = (Address::repeat_byte(0x33), Address::repeat_byte(0x44));
+        let (current, target) = (U256::from(5), U256::from(10));
+
+        let asserter = Asserter::new();
+        asserter.push_success(&Bytes::from(IERC20::allowanceCall::abi_encode_returns(
+            &current,
+        )));
+        let signer = MockSigner {
+            receipt: Some(test_receipt(1, true)),
+            accept_sends: true,
+            ..Default::default()
+        };
+        let client = mock_client_with_signer(asserter, signer)
+            .with_gas_limit(100_000)
+            .with_allowance_reset(true);
+        client
+            .ensure_allowance(token, spender, target)
+            .await
+            .unwrap();
+
+        // Approve 0, wait for it to be mined, then approve the target
+        let calls = client.signer.calls.lock().unwrap().clone();
+        let approve = |amount| MockCall::Send {
+            to: token,
+            data: IERC20::approveCall { spender, amount }.abi_encode().into(),
+        };
+        assert_eq!(
+            calls,
+            vec![
+                approve(U256::ZERO),
+                MockCall::Wait(TxHash::with_last_byte(1)),
+                approve(target),
+            ]
+        );
+
+        // The token accepts that sequence, but not a direct change
+        let mut reset_token = ResetRequiredToken { allowance: current };
+        for call in &calls {
+            if let MockCall::Send { data, .. } = call {
+                let amount = IERC20::approveCall::abi_decode(data).unwrap().amount;
+                reset_token.approve(amount).unwrap();
+            }
+        }
+        assert_eq!(reset_token.allowance, target);
+        let mut reset_token = ResetRequiredToken { allowance: current };
+        assert!(reset_token.approve(target).is_err());
+
+        // No extra approval when starting from zero or already at target
+        assert_eq!(approval_steps(U256::ZERO, target, true), vec![target]);
+        assert!(approval_steps(target, target, true).is_empty());
+    }
+
+    #[test]
+    fn test_group_positions_by_pair() {
+        let position = |pair_index, trade_index| Position {
+            pair_index,
+            trade_index,
+            ..test_position(true)
+        };
+
+        let groups = group_positions_by_pair(vec![
+            position(5, 0),
+            position(1, 2),
+            position(5, 1),
+            position(1, 0),
+        ]);
+
+        let keys: Vec<u16> = groups.iter().map(|(pair_index, _)| *pair_index).collect();
+        assert_eq!(keys, vec![1, 5]);
+        let indices: Vec<u8> = groups[0].1.iter().map(|p| p.trade_index).collect();
+        assert_eq!(indices, vec![2, 0]);
+        assert_eq!(groups[1].1.len(), 2);
+        assert!(group_positions_by_pair(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_client_is_shareable_across_tasks() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<OstiumClient<crate::signer::FordefiSigner>>();
+    }
+
+    #[test]
+    fn test_dedup_positions() {
+        let position = |trade_id: u64, pair_index, trade_index| Position {
+            trade_id: U256::from(trade_id),
+            pair_index,
+            trade_index,
+            ..test_position(true)
+        };
+
+        // Trade 7 read at two indices during a close/reopen race
+        let positions = dedup_positions(vec![
+            position(7, 1, 0),
+            position(8, 1, 1),
+            position(7, 1, 2),
+            position(0, 2, 0),
+            position(0, 2, 1),
+        ]);
+        let slots: Vec<(u16, u8)> = positions
+            .iter()
+            .map(|p| (p.pair_index, p.trade_index))
+            .collect();
+        assert_eq!(slots, vec![(1, 0), (1, 1), (2, 0), (2, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_get_positions_partial() {
+        use crate::contracts::trading_storage::{StoredTrade, TradeInfo};
+        use crate::types::U192;
+
+        let trader = Address::repeat_byte(0xaa);
+        let trade = |pair_index, index, leverage| StoredTrade {
+            collateral: U256::from(100_000_000u64),
+            openPrice: U192::from(50_000u64) * U192::from(10u64).pow(U192::from(18u64)),
+            tp: U192::ZERO,
+            sl: U192::ZERO,
+            trader,
+            leverage,
+            pairIndex: pair_index,
+            index,
+            buy: true,
+        };
+        let info = TradeInfo {
+            tradeId: U192::from(9u64),
+            oiNotional: U192::ZERO,
+            lastTradeBlock: 1,
+            lastTradeTs: 1_700_000_000,
+        };
+        let encoded = |bytes: Vec<u8>| Bytes::from(bytes);
+
+        // Reads run one at a time, so responses are consumed in scan order
+        let asserter = Asserter::new();
+        asserter.push_success(&encoded(
+            ITradingStorage::pairsCountCall::abi_encode_returns(&50),
+        ));
+        asserter.push_success(&encoded(
+            ITradingStorage::maxTradesPerPairCall::abi_encode_returns(&2),
+        ));
+        for pair_index in 0..MAX_SCANNED_PAIRS {
+            match pair_index {
+                1 | 3 => asserter.push_success(&encoded(
+                    ITradingStorage::openTradesCountCall::abi_encode_returns(&1),
+                )),
+                5 => asserter.push_failure_msg("rate limited"),
+                _ => asserter.push_success(&encoded(
+                    ITradingStorage::openTradesCountCall::abi_encode_returns(&0),
+                )),
+            }
+        }
+        let empty = StoredTrade {
+            collateral: U256::ZERO,
+            ..trade(0, 0, 0)
+        };
+        let encoded_trade = |trade: &StoredTrade| {
+            encoded(ITradingStorage::getOpenTradeCall::abi_encode_returns(trade))
+        };
+        let encoded_info = encoded(ITradingStorage::getOpenTradeInfoCall::abi_encode_returns(
+            &info,
+        ));
+        // Pair 1 holds a valid trade, read at both indices during a close/reopen race
+        for _ in 0..2 {
+            asserter.push_success(&encoded_trade(&trade(1, 0, 1_000)));
+            asserter.push_success(&encoded_info);
+        }
+        // Pair 3 holds a corrupt read with zero leverage at index 0
+        asserter.push_success(&encoded_trade(&trade(3, 0, 0)));
+        asserter.push_success(&encoded_info);
+        asserter.push_success(&encoded_trade(&empty));
+
+        let client = mock_client(asserter)
+            .with_read_concurrency(1)
+            .with_positions_cache(Duration::from_secs(60));
+        let result = client.get_positions_partial(Some(trader)).await.unwrap();
+
+        // The duplicate trade collapses into one position
+        assert_eq!(result.positions.len(), 1);
+        let position = &result.positions[0];
+        assert_eq!((position.pair_index, position.trade_index), (1, 0));
+        assert_eq!(position.collateral, 100.0);
+        assert_eq!(position.leverage, 10.0);
+        assert!((position.open_price - 50_000.0).abs() < 1e-6);
+        assert_eq!(position.trade_id, U256::from(9));
+
+        let failed: Vec<u16> = result.errors.iter().map(|(pair, _)| *pair).collect();
+        assert_eq!(failed, vec![3, 5]);
+        assert!(!result.is_complete());
+
+        // An incomplete scan isn't cached
+        let cache = client.positions_cache.as_ref().unwrap();
+        assert!(cache.get(&trader).is_none());
+    }
+
+    #[test]
+    fn test_decode_call() {
+        let trader = Address::repeat_byte(0x11);
+        let calldata = PlaceOrderParams::market(0, 25.0, 10.0, true)
+            .with_open_price(50_000.0)
+            .with_slippage(2.0)
+            .encode_calldata(trader, 3, None);
+
+        let decoded = decode_call(&calldata, None).unwrap();
+        assert_eq!(decoded.contract, "ITrading");
+        assert_eq!(decoded.function, "openTrade");
+        assert_eq!(decoded.arg("t.collateral"), Some("25"));
+        assert_eq!(decoded.arg("t.openPrice"), Some("50000"));
+        assert_eq!(decoded.arg("t.leverage"), Some("10"));
+        assert_eq!(decoded.arg("t.trader"), Some(trader.to_string().as_str()));
+        assert_eq!(decoded.arg("slippageP"), Some("2"));
+
+        let delegated = ITrading::delegatedActionCall {
+            trader,
+            call_data: calldata,
+        };
+        let decoded = decode_call(&delegated.abi_encode(), None).unwrap();
+        assert_eq!(decoded.function, "delegatedAction");
+        assert_eq!(decoded.inner.unwrap().function, "openTrade");
+
+        let approve = IERC20::approveCall {
+            spender: trader,
+            amount: U256::MAX,
+        };
+        let decoded = decode_call(&approve.abi_encode(), None).unwrap();
+        assert_eq!(decoded.contract, "IERC20");
+        assert_eq!(decoded.arg("amount"), Some("unlimited"));
+
+        let redeem = IOstiumVault::redeemCall {
+            shares: U256::from(1_500_000_000_000_000_000u128),
+            receiver: trader,
+            owner: trader,
+        }
+        .abi_encode();
+        let decoded = decode_call(&redeem, Some(18)).unwrap();
+        assert_eq!(decoded.contract, "IOstiumVault");
+        assert_eq!(decoded.arg("shares"), Some("1.5"));
+        let decoded = decode_call(&redeem, None).unwrap();
+        assert_eq!(decoded.arg("shares"), Some("1500000000000000000"));
+
+        assert!(decode_call(&[0xde, 0xad], None).is_err());
+        assert!(decode_call(&[0xde, 0xad, 0xbe, 0xef], None).is_err());
+    }
+}
@@ -1,6 +1,102 @@
 //! Network configuration for Ostium SDK
 
 use alloy::primitives::Address;
+use eyre::{Context, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// Public Arbitrum One RPC endpoint (rate-limited, no API key required)
+pub const ARBITRUM_ONE_PUBLIC_RPC: &str = "https://arb1.arbitrum.io/rpc";
+
+/// Public Arbitrum Sepolia RPC endpoint (rate-limited, no API key required)
+pub const ARBITRUM_SEPOLIA_PUBLIC_RPC: &str = "https://sepolia-rollup.arbitrum.io/rpc";
+
+/// Ostium mainnet subgraph endpoint
+pub const OSTIUM_SUBGRAPH_URL: &str =
+    "https://subgraph.satsuma-prod.com/391a61815d32/ostium/ost-prod/api";
+
+/// Fordefi chain identifier for a chain ID, if Fordefi supports it
+pub fn default_fordefi_chain_name(chain_id: u64) -> Option<&'static str> {
+    match chain_id {
+        42161 => Some("arbitrum_mainnet"),
+        421614 => Some("arbitrum_sepolia"),
+        _ => None,
+    }
+}
+
+/// Default user agent sent with all outbound HTTP requests
+pub const DEFAULT_USER_AGENT: &str = concat!("OstiumRustSDK/", env!("CARGO_PKG_VERSION"));
+
+/// Outbound HTTP identity shared by the price feed and Fordefi clients
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// User agent header value
+    pub user_agent: String,
+    /// Extra headers added to every request (e.g. proxy auth, API keys)
+    pub extra_headers: Vec<(String, String)>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: Vec::new(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Set the user agent
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Add a header sent with every request
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Create a reqwest client builder with the user agent and headers applied
+    pub fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.extra_headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name: {}", name))?;
+            let value = HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for header {}", name))?;
+            headers.insert(name, value);
+        }
+
+        Ok(reqwest::Client::builder()
+            .user_agent(&self.user_agent)
+            .default_headers(headers))
+    }
+}
+
+/// Known Ostium deployments, selectable at runtime (e.g. from a `--network` flag)
+#[derive(Debug, Clone)]
+pub enum Network {
+    /// Arbitrum One mainnet (requires `ALCHEMY_API_KEY`)
+    ArbitrumOne,
+    /// Arbitrum Sepolia testnet (public RPC)
+    ArbitrumSepolia,
+    /// Any other deployment
+    Custom(Box<NetworkConfig>),
+}
+
+impl std::str::FromStr for Network {
+    type Err = eyre::Report;
+
+    /// Parse a preset name: `arbitrum-one`/`mainnet` or `arbitrum-sepolia`/`testnet`
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "arbitrum-one" | "arbitrum_one" | "mainnet" => Ok(Network::ArbitrumOne),
+            "arbitrum-sepolia" | "arbitrum_sepolia" | "testnet" => Ok(Network::ArbitrumSepolia),
+            _ => eyre::bail!("Unknown network: {}", s),
+        }
+    }
+}
 
 /// Network configuration containing RPC URLs and contract addresses (Arbitrum One mainnet)
 #[derive(Debug, Clone)]
@@ -19,6 +115,16 @@ pub struct NetworkConfig {
     pub vault: Option<Address>,
     /// Auto-withdraw contract address (approves OLP for automatic withdrawals)
     pub auto_withdraw: Option<Address>,
+    /// Ostium subgraph URL (optional, for pair and trade history queries)
+    pub subgraph_url: Option<String>,
+    /// Uniswap V3 SwapRouter02 address (optional, for orders funded in other tokens)
+    pub router: Option<Address>,
+    /// Outbound HTTP settings for the price feed and Fordefi API
+    pub http: HttpConfig,
+    /// Fordefi chain identifier (e.g. `arbitrum_mainnet`) used by
+    /// [`FordefiSigner::from_config`](crate::signer::FordefiSigner::from_config);
+    /// defaults per chain ID
+    pub fordefi_chain_name: Option<String>,
 }
 
 impl Default for NetworkConfig {
@@ -33,9 +139,57 @@ impl NetworkConfig {
         let alchemy_key = std::env::var("ALCHEMY_API_KEY")
             .expect("ALCHEMY_API_KEY environment variable must be set");
 
+        Self::arbitrum_one(format!(
+            "https://arb-mainnet.g.alchemy.com/v2/{}",
+            alchemy_key
+        ))
+    }
+
+    /// Start building a fully custom configuration
+    ///
+    /// Unlike [`NetworkConfig::new`], this reads no environment variables.
+    pub fn builder() -> NetworkConfigBuilder {
+        NetworkConfigBuilder::default()
+    }
+
+    /// Start a builder from this configuration, e.g. to override a preset
+    ///
+    /// A Fordefi chain name equal to the chain ID's default is left unset, so
+    /// it follows an overridden chain ID.
+    pub fn into_builder(self) -> NetworkConfigBuilder {
+        let derived = default_fordefi_chain_name(self.chain_id);
+        NetworkConfigBuilder {
+            chain_id: Some(self.chain_id),
+            rpc_url: Some(self.rpc_url),
+            usdc: Some(self.usdc),
+            trading: Some(self.trading),
+            trading_storage: Some(self.trading_storage),
+            vault: self.vault,
+            auto_withdraw: self.auto_withdraw,
+            subgraph_url: self.subgraph_url,
+            router: self.router,
+            http: self.http,
+            fordefi_chain_name: self
+                .fordefi_chain_name
+                .filter(|name| Some(name.as_str()) != derived),
+        }
+    }
+
+    /// Arbitrum One mainnet configuration using the public Arbitrum RPC
+    ///
+    /// Requires no API key, which makes it convenient for trying out read-only
+    /// features. The public endpoint is rate-limited and may reject bursts of
+    /// requests (e.g. a full `get_positions` scan), so use a dedicated RPC
+    /// provider for production.
+    pub fn arbitrum_one_public() -> Self {
+        Self::arbitrum_one(ARBITRUM_ONE_PUBLIC_RPC)
+    }
+
+    /// Arbitrum One mainnet contract addresses with the given RPC URL
+    fn arbitrum_one(rpc_url: impl Into<String>) -> Self {
         Self {
             chain_id: 42161,
-            rpc_url: format!("https://arb-mainnet.g.alchemy.com/v2/{}", alchemy_key),
+            rpc_url: rpc_url.into(),
             usdc: "0xaf88d065e77c8cC2239327C5EDb3A432268e5831"
                 .parse()
                 .unwrap(),
@@ -55,6 +209,51 @@ impl NetworkConfig {
                     .parse()
                     .unwrap(),
             ),
+            subgraph_url: Some(OSTIUM_SUBGRAPH_URL.to_string()),
+            router: Some(
+                "0x68b3465833fb72A70ecDF485E0e4C7bD8665Fc45"
+                    .parse()
+                    .unwrap(),
+            ),
+            http: HttpConfig::default(),
+            fordefi_chain_name: default_fordefi_chain_name(42161).map(String::from),
+        }
+    }
+
+    /// Arbitrum Sepolia testnet configuration using the public Sepolia RPC
+    ///
+    /// The testnet has no OLP vault or subgraph configured.
+    fn arbitrum_sepolia() -> Self {
+        Self {
+            chain_id: 421614,
+            rpc_url: ARBITRUM_SEPOLIA_PUBLIC_RPC.to_string(),
+            usdc: "0xe73B11Fb1e3eeEe8AF2a23079A4410Fe1B370548"
+                .parse()
+                .unwrap(),
+            trading: "0x2A9B9c988393f46a2537B0ff11E98c2C15a95afe"
+                .parse()
+                .unwrap(),
+            trading_storage: "0x0b9F5243B29938668c9Cfbd7557A389EC7Ef88b8"
+                .parse()
+                .unwrap(),
+            vault: None,
+            auto_withdraw: None,
+            subgraph_url: None,
+            router: None,
+            http: HttpConfig::default(),
+            fordefi_chain_name: default_fordefi_chain_name(421614).map(String::from),
+        }
+    }
+
+    /// Configuration for a network preset
+    ///
+    /// `Network::ArbitrumOne` behaves like [`NetworkConfig::new`] and panics if
+    /// `ALCHEMY_API_KEY` is not set.
+    pub fn for_network(network: Network) -> Self {
+        match network {
+            Network::ArbitrumOne => Self::new(),
+            Network::ArbitrumSepolia => Self::arbitrum_sepolia(),
+            Network::Custom(config) => *config,
         }
     }
 
@@ -63,6 +262,11 @@ impl NetworkConfig {
         Self::new()
     }
 
+    /// Whether the RPC URL uses a WebSocket scheme (`ws://` or `wss://`)
+    pub fn is_websocket(&self) -> bool {
+        self.rpc_url.starts_with("ws://") || self.rpc_url.starts_with("wss://")
+    }
+
     /// Create custom configuration with specific RPC URL
     pub fn with_rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
         self.rpc_url = rpc_url.into();
@@ -75,9 +279,258 @@ impl NetworkConfig {
         self
     }
 
+    /// Set the subgraph URL
+    pub fn with_subgraph_url(mut self, subgraph_url: impl Into<String>) -> Self {
+        self.subgraph_url = Some(subgraph_url.into());
+        self
+    }
+
+    /// Set the DEX router address
+    pub fn with_router(mut self, router: Address) -> Self {
+        self.router = Some(router);
+        self
+    }
+
+    /// Set the outbound HTTP settings
+    pub fn with_http_config(mut self, http: HttpConfig) -> Self {
+        self.http = http;
+        self
+    }
+
     /// Set the auto-withdraw address
     pub fn with_auto_withdraw(mut self, auto_withdraw: Address) -> Self {
         self.auto_withdraw = Some(auto_withdraw);
         self
     }
+
+    /// Set the Fordefi chain identifier (e.g. `arbitrum_sepolia`)
+    pub fn with_fordefi_chain_name(mut self, chain_name: impl Into<String>) -> Self {
+        self.fordefi_chain_name = Some(chain_name.into());
+        self
+    }
+
+    /// Fordefi chain identifier, or an error if none is configured
+    pub fn fordefi_chain(&self) -> Result<&str> {
+        self.fordefi_chain_name.as_deref().ok_or_else(|| {
+            eyre::eyre!(
+                "No Fordefi chain name for chain ID {}; set NetworkConfig::with_fordefi_chain_name",
+                self.chain_id
+            )
+        })
+    }
+}
+
+/// Builder for a [`NetworkConfig`] with every field set explicitly
+///
+/// `chain_id`, `rpc_url`, `usdc`, `trading`, and `trading_storage` are
+/// required; the vault, auto-withdraw, subgraph, and router are optional.
+/// The Fordefi chain name defaults from the chain ID when not set.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkConfigBuilder {
+    chain_id: Option<u64>,
+    rpc_url: Option<String>,
+    usdc: Option<Address>,
+    trading: Option<Address>,
+    trading_storage: Option<Address>,
+    vault: Option<Address>,
+    auto_withdraw: Option<Address>,
+    subgraph_url: Option<String>,
+    router: Option<Address>,
+    http: HttpConfig,
+    fordefi_chain_name: Option<String>,
+}
+
+impl NetworkConfigBuilder {
+    /// Set the chain ID
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set the RPC URL (`http(s)://` or `ws(s)://`)
+    pub fn with_rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    /// Set the USDC token address
+    pub fn with_usdc(mut self, usdc: Address) -> Self {
+        self.usdc = Some(usdc);
+        self
+    }
+
+    /// Set the Trading contract address
+    pub fn with_trading(mut self, trading: Address) -> Self {
+        self.trading = Some(trading);
+        self
+    }
+
+    /// Set the TradingStorage contract address
+    pub fn with_trading_storage(mut self, trading_storage: Address) -> Self {
+        self.trading_storage = Some(trading_storage);
+        self
+    }
+
+    /// Set the OLP vault address
+    pub fn with_vault(mut self, vault: Address) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    /// Set the auto-withdraw address
+    pub fn with_auto_withdraw(mut self, auto_withdraw: Address) -> Self {
+        self.auto_withdraw = Some(auto_withdraw);
+        self
+    }
+
+    /// Set the subgraph URL
+    pub fn with_subgraph_url(mut self, subgraph_url: impl Into<String>) -> Self {
+        self.subgraph_url = Some(subgraph_url.into());
+        self
+    }
+
+    /// Set the DEX router address
+    pub fn with_router(mut self, router: Address) -> Self {
+        self.router = Some(router);
+        self
+    }
+
+    /// Set the outbound HTTP settings
+    pub fn with_http_config(mut self, http: HttpConfig) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Set the Fordefi chain identifier (defaults from the chain ID)
+    pub fn with_fordefi_chain_name(mut self, chain_name: impl Into<String>) -> Self {
+        self.fordefi_chain_name = Some(chain_name.into());
+        self
+    }
+
+    /// Validate the fields and build the configuration
+    pub fn build(self) -> Result<NetworkConfig> {
+        let chain_id = self
+            .chain_id
+            .ok_or_else(|| eyre::eyre!("chain_id is required"))?;
+        eyre::ensure!(chain_id != 0, "chain_id must be non-zero");
+
+        let rpc_url = self
+            .rpc_url
+            .ok_or_else(|| eyre::eyre!("rpc_url is required"))?;
+        let scheme_ok = ["http://", "https://", "ws://", "wss://"]
+            .iter()
+            .any(|scheme| rpc_url.starts_with(scheme));
+        eyre::ensure!(
+            scheme_ok,
+            "rpc_url must be an http(s) or ws(s) URL: {}",
+            rpc_url
+        );
+
+        let required = |name: &str, address: Option<Address>| -> Result<Address> {
+            let address = address.ok_or_else(|| eyre::eyre!("{} address is required", name))?;
+            eyre::ensure!(!address.is_zero(), "{} address must be non-zero", name);
+            Ok(address)
+        };
+        let usdc = required("usdc", self.usdc)?;
+        let trading = required("trading", self.trading)?;
+        let trading_storage = required("trading_storage", self.trading_storage)?;
+
+        let optional = [
+            ("vault", self.vault),
+            ("auto_withdraw", self.auto_withdraw),
+            ("router", self.router),
+        ];
+        for (name, address) in optional {
+            eyre::ensure!(
+                !address.is_some_and(|address| address.is_zero()),
+                "{} address must be non-zero",
+                name
+            );
+        }
+
+        Ok(NetworkConfig {
+            chain_id,
+            rpc_url,
+            usdc,
+            trading,
+            trading_storage,
+            vault: self.vault,
+            auto_withdraw: self.auto_withdraw,
+            subgraph_url: self.subgraph_url,
+            router: self.router,
+            http: self.http,
+            fordefi_chain_name: self
+                .fordefi_chain_name
+                .or_else(|| default_fordefi_chain_name(chain_id).map(String::from)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_presets() {
+        let network: Network = "testnet".parse().unwrap();
+        let sepolia = NetworkConfig::for_network(network);
+        assert_eq!(sepolia.chain_id, 421614);
+        assert!(sepolia.vault.is_none());
+
+        let custom = NetworkConfig::arbitrum_one_public().with_rpc_url("http://localhost:8545");
+        let config = NetworkConfig::for_network(Network::Custom(Box::new(custom)));
+        assert_eq!(config.rpc_url, "http://localhost:8545");
+
+        assert!("polygon".parse::<Network>().is_err());
+    }
+
+    #[test]
+    fn test_builder_validates() {
+        let address = Address::repeat_byte(1);
+        let builder = NetworkConfig::builder()
+            .with_chain_id(31337)
+            .with_rpc_url("http://localhost:8545")
+            .with_usdc(address)
+            .with_trading(address)
+            .with_trading_storage(address);
+
+        let config = builder.clone().build().unwrap();
+        assert_eq!(config.chain_id, 31337);
+        assert!(config.vault.is_none() && config.subgraph_url.is_none());
+
+        assert!(builder
+            .clone()
+            .with_rpc_url("localhost:8545")
+            .build()
+            .is_err());
+        assert!(builder.clone().with_vault(Address::ZERO).build().is_err());
+        assert!(builder.with_usdc(Address::ZERO).build().is_err());
+        assert!(NetworkConfig::builder().build().is_err());
+    }
+
+    #[test]
+    fn test_fordefi_chain_name() {
+        let sepolia = NetworkConfig::for_network(Network::ArbitrumSepolia);
+        assert_eq!(sepolia.fordefi_chain().unwrap(), "arbitrum_sepolia");
+        assert_eq!(
+            NetworkConfig::arbitrum_one_public()
+                .fordefi_chain()
+                .unwrap(),
+            "arbitrum_mainnet"
+        );
+
+        let address = Address::repeat_byte(1);
+        let builder = NetworkConfig::builder()
+            .with_chain_id(31337)
+            .with_rpc_url("http://localhost:8545")
+            .with_usdc(address)
+            .with_trading(address)
+            .with_trading_storage(address);
+        assert!(builder.clone().build().unwrap().fordefi_chain().is_err());
+        let config = builder
+            .with_fordefi_chain_name("ethereum_mainnet")
+            .build()
+            .unwrap();
+        assert_eq!(config.fordefi_chain().unwrap(), "ethereum_mainnet");
+    }
 }
@@ -0,0 +1,390 @@
+//! TOML deployment config for building an [`OstiumClient`]
+//!
+//! Enabled with the `config-file` feature. A config file keeps the network,
+//! signer credentials, and client defaults in one place:
+//!
+//! ```toml
+//! [network]
+//! preset = "mainnet"            # or "testnet"; omit to set every field below
+//! rpc_url = "https://arb-mainnet.g.alchemy.com/v2/KEY"
+//! # chain_id, usdc, trading, trading_storage, vault, auto_withdraw,
+//! # subgraph_url, router, fordefi_chain_name override the preset
+//!
+//! [signer]
+//! type = "fordefi"
+//! access_token_env = "FORDEFI_JWT_TOKEN"   # or access_token = "..."
+//! private_key_path = "keys/pk.pem"         # relative to the config file
+//! # vault_id = "..."                       # default: first EVM vault
+//!
+//! [defaults]
+//! slippage = 1.5
+//! gas_buffer_percent = 30
+//! # gas_limit = 3000000
+//! ```
+
+use crate::client::OstiumClient;
+use crate::config::{Network, NetworkConfig, NetworkConfigBuilder};
+use crate::signer::FordefiSigner;
+use alloy::primitives::Address;
+use eyre::{Context, Result};
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Parsed contents of a TOML config file
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    /// Network the client connects to
+    pub network: NetworkConfig,
+    /// Signer backend and its credentials
+    pub signer: SignerConfig,
+    /// Client-wide defaults
+    pub defaults: ClientDefaults,
+}
+
+/// `[signer]` section of a config file
+#[derive(Clone)]
+pub enum SignerConfig {
+    /// Fordefi MPC wallet
+    Fordefi {
+        /// Literal access token, if given in the file
+        access_token: Option<String>,
+        /// Environment variable holding the access token
+        access_token_env: Option<String>,
+        /// Path of the API user's P-256 private key (PEM)
+        private_key_path: PathBuf,
+        /// Vault to sign with (the first EVM vault if unset)
+        vault_id: Option<String>,
+    },
+}
+
+impl fmt::Debug for SignerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerConfig::Fordefi {
+                access_token,
+                access_token_env,
+                private_key_path,
+                vault_id,
+            } => f
+                .debug_struct("Fordefi")
+                .field("access_token", &access_token.as_ref().map(|_| "<redacted>"))
+                .field("access_token_env", access_token_env)
+                .field("private_key_path", private_key_path)
+                .field("vault_id", vault_id)
+                .finish(),
+        }
+    }
+}
+
+/// `[defaults]` section of a config file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClientDefaults {
+    /// Slippage percentage for orders and closes that don't set one
+    pub slippage: Option<f64>,
+    /// Safety margin added to estimated gas, in percent
+    pub gas_buffer_percent: Option<u64>,
+    /// Fixed gas limit for every write
+    pub gas_limit: Option<u64>,
+}
+
+/// File layout as written, before presets and defaults are applied
+#[derive(Deserialize)]
+struct RawConfigFile {
+    network: RawNetwork,
+    signer: RawSigner,
+    #[serde(default)]
+    defaults: ClientDefaults,
+}
+
+#[derive(Deserialize)]
+struct RawNetwork {
+    preset: Option<String>,
+    rpc_url: Option<String>,
+    chain_id: Option<u64>,
+    usdc: Option<Address>,
+    trading: Option<Address>,
+    trading_storage: Option<Address>,
+    vault: Option<Address>,
+    auto_withdraw: Option<Address>,
+    router: Option<Address>,
+    subgraph_url: Option<String>,
+    fordefi_chain_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawSigner {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    access_token: Option<String>,
+    access_token_env: Option<String>,
+    private_key_path: Option<PathBuf>,
+    vault_id: Option<String>,
+}
+
+impl ConfigFile {
+    /// Read and parse a config file
+    ///
+    /// A relative `private_key_path` is resolved against the file's directory.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let mut config = Self::parse(&text)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+
+        let SignerConfig::Fordefi {
+            private_key_path, ..
+        } = &mut config.signer;
+        if private_key_path.is_relative() {
+            if let Some(dir) = path.parent() {
+                *private_key_path = dir.join(&*private_key_path);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Parse config file contents
+    pub fn parse(text: &str) -> Result<Self> {
+        let raw: RawConfigFile = toml::from_str(text).context("Failed to parse TOML")?;
+
+        Ok(Self {
+            network: raw.network.into_config()?,
+            signer: raw.signer.into_config()?,
+            defaults: raw.defaults,
+        })
+    }
+}
+
+impl OstiumClient<FordefiSigner> {
+    /// Build a client from a TOML config file (`config-file` feature)
+    ///
+    /// Connects the configured signer, then applies the file's defaults. See
+    /// the [module docs](crate::config_file) for the file format.
+    pub async fn from_config_file(path: impl AsRef<Path>) -> Result<Self> {
+        let ConfigFile {
+            network,
+            signer,
+            defaults,
+        } = ConfigFile::load(path)?;
+
+        let SignerConfig::Fordefi {
+            access_token,
+            access_token_env,
+            private_key_path,
+            vault_id,
+        } = signer;
+        let access_token = match (access_token, access_token_env) {
+            (Some(token), _) => token,
+            (None, Some(var)) => std::env::var(&var)
+                .with_context(|| format!("Environment variable {} is not set", var))?,
+            (None, None) => eyre::bail!("[signer] needs access_token or access_token_env"),
+        };
+        let private_key_pem = std::fs::read_to_string(&private_key_path).with_context(|| {
+            format!(
+                "Failed to read Fordefi private key {}",
+                private_key_path.display()
+            )
+        })?;
+
+        let signer = match vault_id {
+            Some(vault_id) => {
+                FordefiSigner::with_vault_id_and_http_config(
+                    access_token,
+                    &private_key_pem,
+                    vault_id,
+                    &network.rpc_url,
+                    &network.http,
+                )
+                .await?
+            }
+            None => {
+                FordefiSigner::discover_with_http_config(
+                    access_token,
+                    &private_key_pem,
+                    &network.rpc_url,
+                    &network.http,
+                )
+                .await?
+            }
+        };
+        let signer = signer.with_chain_name(network.fordefi_chain()?);
+
+        let mut client = OstiumClient::new(signer, network).await?;
+        if let Some(slippage) = defaults.slippage {
+            client = client.with_default_slippage(slippage);
+        }
+        if let Some(buffer_percent) = defaults.gas_buffer_percent {
+            client = client.with_gas_buffer(buffer_percent);
+        }
+        if let Some(gas_limit) = defaults.gas_limit {
+            client = client.with_gas_limit(gas_limit);
+        }
+        Ok(client)
+    }
+}
+
+impl RawNetwork {
+    /// Build the network config from a preset plus overrides, or entirely from fields
+    ///
+    /// Both go through [`NetworkConfigBuilder::build`], so overrides get the
+    /// same validation as a config built from scratch.
+    fn into_config(self) -> Result<NetworkConfig> {
+        let mut builder = match self.preset {
+            Some(preset) => match preset.parse::<Network>()? {
+                // Start from the public RPC so the preset doesn't need ALCHEMY_API_KEY
+                Network::ArbitrumOne => NetworkConfig::arbitrum_one_public(),
+                network => NetworkConfig::for_network(network),
+            }
+            .into_builder(),
+            None => NetworkConfigBuilder::default(),
+        };
+
+        if let Some(chain_id) = self.chain_id {
+            builder = builder.with_chain_id(chain_id);
+        }
+        if let Some(rpc_url) = self.rpc_url {
+            builder = builder.with_rpc_url(rpc_url);
+        }
+        if let Some(usdc) = self.usdc {
+            builder = builder.with_usdc(usdc);
+        }
+        if let Some(trading) = self.trading {
+            builder = builder.with_trading(trading);
+        }
+        if let Some(trading_storage) = self.trading_storage {
+            builder = builder.with_trading_storage(trading_storage);
+        }
+        if let Some(vault) = self.vault {
+            builder = builder.with_vault(vault);
+        }
+        if let Some(auto_withdraw) = self.auto_withdraw {
+            builder = builder.with_auto_withdraw(auto_withdraw);
+        }
+        if let Some(router) = self.router {
+            builder = builder.with_router(router);
+        }
+        if let Some(subgraph_url) = self.subgraph_url {
+            builder = builder.with_subgraph_url(subgraph_url);
+        }
+        if let Some(chain_name) = self.fordefi_chain_name {
+            builder = builder.with_fordefi_chain_name(chain_name);
+        }
+        builder.build()
+    }
+}
+
+impl RawSigner {
+    fn into_config(self) -> Result<SignerConfig> {
+        match self.kind.as_deref().unwrap_or("fordefi") {
+            "fordefi" => Ok(SignerConfig::Fordefi {
+                access_token: self.access_token,
+                access_token_env: self.access_token_env,
+                private_key_path: self
+                    .private_key_path
+                    .ok_or_else(|| eyre::eyre!("[signer] private_key_path is required"))?,
+                vault_id: self.vault_id,
+            }),
+            other => eyre::bail!("Unsupported signer type: {}", other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_preset_with_overrides() {
+        let config = ConfigFile::parse(
+            r#"
+            [network]
+            preset = "mainnet"
+            rpc_url = "https://rpc.example"
+            router = "0x1111111111111111111111111111111111111111"
+
+            [signer]
+            type = "fordefi"
+            access_token_env = "FORDEFI_JWT_TOKEN"
+            private_key_path = "keys/pk.pem"
+
+            [defaults]
+            slippage = 1
+            gas_buffer_percent = 30
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.network.chain_id, 42161);
+        assert_eq!(config.network.rpc_url, "https://rpc.example");
+        assert!(config.network.vault.is_some());
+        assert_eq!(config.network.router, Some(Address::repeat_byte(0x11)));
+        assert_eq!(config.defaults.slippage, Some(1.0));
+        assert_eq!(config.defaults.gas_buffer_percent, Some(30));
+        assert_eq!(config.defaults.gas_limit, None);
+        let SignerConfig::Fordefi {
+            access_token_env,
+            vault_id,
+            ..
+        } = config.signer;
+        assert_eq!(access_token_env.as_deref(), Some("FORDEFI_JWT_TOKEN"));
+        assert!(vault_id.is_none());
+
+        // Overriding the chain ID re-derives the Fordefi chain name
+        let signer = "[signer]\nprivate_key_path = \"pk.pem\"\n";
+        let sepolia = format!(
+            "[network]\npreset = \"mainnet\"\nchain_id = 421614\n{}",
+            signer
+        );
+        let network = ConfigFile::parse(&sepolia).unwrap().network;
+        assert_eq!(network.fordefi_chain().unwrap(), "arbitrum_sepolia");
+        let named = format!(
+            "[network]\npreset = \"mainnet\"\nchain_id = 421614\n\
+             fordefi_chain_name = \"custom\"\n{}",
+            signer
+        );
+        let network = ConfigFile::parse(&named).unwrap().network;
+        assert_eq!(network.fordefi_chain().unwrap(), "custom");
+    }
+
+    #[test]
+    fn test_debug_redacts_access_token() {
+        let config = ConfigFile::parse(
+            "[network]\npreset = \"testnet\"\n\
+             [signer]\naccess_token = \"secret-jwt\"\nprivate_key_path = \"pk.pem\"\n",
+        )
+        .unwrap();
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("secret-jwt"));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_sections() {
+        let signer = "[signer]\nprivate_key_path = \"pk.pem\"\n";
+
+        // Without a preset the builder's required fields apply
+        let missing = format!("[network]\nchain_id = 1\n{}", signer);
+        assert!(ConfigFile::parse(&missing).is_err());
+
+        let bad_address = format!(
+            "[network]\npreset = \"testnet\"\nvault = \"0x12\"\n{}",
+            signer
+        );
+        assert!(ConfigFile::parse(&bad_address).is_err());
+
+        // Preset overrides are validated like the builder's fields
+        for field in [
+            "usdc = \"0x0000000000000000000000000000000000000000\"",
+            "rpc_url = \"localhost:8545\"",
+            "chain_id = 0",
+        ] {
+            let config = format!("[network]\npreset = \"mainnet\"\n{}\n{}", field, signer);
+            assert!(ConfigFile::parse(&config).is_err(), "{field}");
+        }
+
+        let bad_type = "[network]\npreset = \"testnet\"\n[signer]\ntype = \"kms\"\n";
+        assert!(ConfigFile::parse(bad_type).is_err());
+        assert!(ConfigFile::parse(signer).is_err());
+    }
+}
@@ -25,14 +25,42 @@ pub const MAX_LEVERAGE: f64 = 1000.0;
 /// Maximum slippage allowed (100%)
 pub const MAX_SLIPPAGE: f64 = 100.0;
 
+/// Largest USDC amount accepted for a single vault deposit (1 billion)
+///
+/// Catches raw 6-decimal values passed where a USDC amount is expected.
+pub const MAX_DEPOSIT_AMOUNT: f64 = 1_000_000_000.0;
+
+/// Share of collateral a position can lose before it is liquidated (90%)
+pub const LIQUIDATION_THRESHOLD_PERCENT: f64 = 90.0;
+
 /// Default slippage (2%)
 pub const DEFAULT_SLIPPAGE: f64 = 2.0;
 
-/// Scale a floating point value to U256 with specified decimals
+/// Price samples taken to size auto slippage
+pub const AUTO_SLIPPAGE_SAMPLES: usize = 5;
+
+/// Delay between auto slippage price samples (5 samples span ~2 seconds)
+pub const AUTO_SLIPPAGE_SAMPLE_INTERVAL_MS: u64 = 500;
+
+/// Slippage recommended on top of the spread for price moves before execution (0.1%)
+pub const RECOMMENDED_SLIPPAGE_BUFFER: f64 = 0.1;
+
+/// Extra slippage recommended for an order that would take all remaining open interest (2%)
+pub const DEPTH_IMPACT_SLIPPAGE: f64 = 2.0;
+
+/// Uniswap V3 pool fee tier used when swapping into USDC (500 = 0.05%)
+pub const SWAP_FEE_TIER: u32 = 500;
+
+/// Scale a floating point value to U256 with specified decimals, rounding to
+/// the nearest unit
 pub fn scale_to_decimals(value: f64, decimals: u8) -> U256 {
-    let multiplier = 10u64.pow(decimals as u32);
-    let scaled = (value * multiplier as f64) as u128;
-    U256::from(scaled)
+    // Scale the integer and fractional parts separately so large values
+    // (e.g. prices with 18 decimals) don't pick up f64 rounding noise
+    let multiplier = U256::from(10u64).pow(U256::from(decimals));
+    let whole = value.trunc();
+    let fraction = value - whole;
+    let scaled_fraction = (fraction * 10f64.powi(decimals as i32)).round() as u128;
+    U256::from(whole as u128) * multiplier + U256::from(scaled_fraction)
 }
 
 /// Unscale a U256 value to floating point with specified decimals
@@ -53,13 +81,41 @@ pub fn scale_price(price: f64) -> U256 {
 }
 
 /// Scale leverage (2 decimals / basis points / 100)
+///
+/// Rounds to the nearest 0.01x (half away from zero), so 2.999x encodes as
+/// 300 rather than truncating to 299. Out-of-range inputs saturate; use
+/// [`checked_scale_leverage`] to reject them.
 pub fn scale_leverage(leverage: f64) -> u32 {
-    (leverage * 100.0) as u32
+    (leverage * 100.0).round() as u32
+}
+
+/// Scale leverage, erroring if the encoded value doesn't map back to it
+///
+/// Rounding moves a finite, in-range leverage by at most 0.005x; anything
+/// further (NaN, infinity, negative or overflowing values) is rejected.
+pub fn checked_scale_leverage(leverage: f64) -> eyre::Result<u32> {
+    let scaled = scale_leverage(leverage);
+    let round_trip = scaled as f64 / 100.0;
+    eyre::ensure!(
+        leverage.is_finite() && (round_trip - leverage).abs() <= 0.005 + 1e-9,
+        "Leverage {} cannot be encoded (nearest encodable value is {}x)",
+        leverage,
+        round_trip
+    );
+    Ok(scaled)
 }
 
 /// Scale slippage (2 decimals / percentage * 100)
 pub fn scale_slippage(slippage_percent: f64) -> u16 {
-    (slippage_percent * 100.0) as u16
+    slippage_basis_points(slippage_percent).min(u16::MAX as u32) as u16
+}
+
+/// Convert a slippage percentage to the contracts' `slippageP` units (2% = 200)
+///
+/// Shared by the open and close encoders so both bound execution identically.
+/// Rounds to the nearest basis point, like [`scale_leverage`].
+pub fn slippage_basis_points(slippage_percent: f64) -> u32 {
+    (slippage_percent * 100.0).round() as u32
 }
 
 #[cfg(test)]
@@ -74,6 +130,33 @@ mod tests {
         assert_eq!(scale_usdc(0.5), U256::from(500_000u64));
     }
 
+    #[test]
+    fn test_scale_to_decimals_rounds() {
+        // The previous implementation truncated the scaled f64
+        let truncated = |value: f64, decimals: u8| {
+            U256::from((value * 10u64.pow(decimals as u32) as f64) as u128)
+        };
+
+        // 8.2 * 1e6 lands just below 8_200_000 in f64
+        assert_eq!(truncated(8.2, 6), U256::from(8_199_999u64));
+        assert_eq!(scale_to_decimals(8.2, 6), U256::from(8_200_000u64));
+
+        // Half a unit in the last place rounds up instead of to zero
+        assert_eq!(truncated(0.0000005, 6), U256::ZERO);
+        assert_eq!(scale_to_decimals(0.0000005, 6), U256::from(1u64));
+        assert_eq!(scale_to_decimals(0.00000049, 6), U256::ZERO);
+
+        // Exact values are unchanged
+        for value in [0.0, 1.0, 0.5, 250.0, 1_000_000.0] {
+            assert_eq!(scale_to_decimals(value, 6), truncated(value, 6), "{value}");
+        }
+        let half = U256::from(10u64).pow(U256::from(17u64)) * U256::from(5u64);
+        assert_eq!(
+            scale_to_decimals(50_000.5, 18),
+            U256::from(50_000u64) * U256::from(10u64).pow(U256::from(18u64)) + half
+        );
+    }
+
     #[test]
     fn test_scale_price() {
         // $50,000 with 18 decimals
@@ -87,6 +170,22 @@ mod tests {
         assert_eq!(scale_leverage(100.0), 10000);
         // 2x leverage = 200
         assert_eq!(scale_leverage(2.0), 200);
+        // Rounds instead of truncating
+        assert_eq!(scale_leverage(2.999), 300);
+        assert_eq!(scale_leverage(9.999), 1000);
+        // Halves round away from zero
+        assert_eq!(scale_leverage(2.005), 201);
+        assert_eq!(scale_leverage(2.0049), 200);
+    }
+
+    #[test]
+    fn test_checked_scale_leverage() {
+        assert_eq!(checked_scale_leverage(9.999).unwrap(), 1000);
+        assert_eq!(checked_scale_leverage(100.0).unwrap(), 10_000);
+        assert!(checked_scale_leverage(f64::NAN).is_err());
+        assert!(checked_scale_leverage(f64::INFINITY).is_err());
+        assert!(checked_scale_leverage(-5.0).is_err());
+        assert!(checked_scale_leverage(1e12).is_err());
     }
 
     #[test]
@@ -95,5 +194,57 @@ mod tests {
         assert_eq!(scale_slippage(2.0), 200);
         // 0.5% slippage = 50
         assert_eq!(scale_slippage(0.5), 50);
+        // 0.29% is stored as 28.999... basis points and must not truncate
+        assert_eq!(scale_slippage(0.29), 29);
+    }
+
+    #[test]
+    fn test_slippage_basis_points() {
+        assert_eq!(slippage_basis_points(0.5), 50);
+        assert_eq!(slippage_basis_points(2.0), 200);
+        assert_eq!(slippage_basis_points(2.5), 250);
+        assert_eq!(slippage_basis_points(100.0), 10_000);
+        assert_eq!(scale_slippage(2.5) as u32, slippage_basis_points(2.5));
+    }
+
+    mod props {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn usdc_round_trips(micros in 0u64..1_000_000_000_000_000) {
+                let raw = U256::from(micros);
+                let amount = unscale_from_decimals(raw, USDC_DECIMALS);
+                prop_assert_eq!(scale_usdc(amount), raw);
+            }
+
+            #[test]
+            fn whole_prices_scale_exactly(price in 0u64..1_000_000_000) {
+                let expected = U256::from(price) * U256::from(10u64).pow(U256::from(18u64));
+                prop_assert_eq!(scale_price(price as f64), expected);
+            }
+
+            #[test]
+            fn fractional_prices_round_trip(price in 0.0f64..10_000_000.0) {
+                let round_trip = unscale_from_decimals(scale_price(price), PRICE_DECIMALS);
+                prop_assert!((round_trip - price).abs() <= price * 1e-12);
+            }
+
+            // Leverage and slippage scaling rounds, so every two-decimal
+            // value (including ones like 0.29 = 28.999... * 100) is exact
+            #[test]
+            fn leverage_round_trips_exactly(scaled in 200u32..=100_000) {
+                let leverage = scaled as f64 / 100.0;
+                prop_assert_eq!(scale_leverage(leverage), scaled);
+                prop_assert_eq!(checked_scale_leverage(leverage).unwrap(), scaled);
+            }
+
+            #[test]
+            fn slippage_round_trips_exactly(scaled in 0u16..=10_000) {
+                let slippage = scaled as f64 / 100.0;
+                prop_assert_eq!(scale_slippage(slippage), scaled);
+            }
+        }
     }
 }
@@ -1,10 +1,12 @@
 //! Contract bindings for Ostium protocol
 
+pub mod router;
 pub mod trading;
 pub mod trading_storage;
 pub mod usdc;
 pub mod vault;
 
+pub use router::*;
 pub use trading::*;
 pub use trading_storage::*;
 pub use usdc::*;
@@ -0,0 +1,23 @@
+//! DEX router contract bindings (Uniswap V3 SwapRouter02)
+
+use alloy::sol;
+
+sol! {
+    /// Minimal Uniswap V3 SwapRouter02 interface for single-pool swaps
+    #[sol(rpc)]
+    interface ISwapRouter {
+        /// Parameters for swapping up to `amountInMaximum` of `tokenIn` for exactly `amountOut` of `tokenOut`
+        struct ExactOutputSingleParams {
+            address tokenIn;
+            address tokenOut;
+            uint24 fee;
+            address recipient;
+            uint256 amountOut;
+            uint256 amountInMaximum;
+            uint160 sqrtPriceLimitX96;
+        }
+
+        /// Swaps as little `tokenIn` as possible for exactly `amountOut` of `tokenOut`
+        function exactOutputSingle(ExactOutputSingleParams calldata params) external payable returns (uint256 amountIn);
+    }
+}
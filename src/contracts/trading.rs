@@ -1,6 +1,7 @@
 //! Trading contract bindings
 
 use alloy::sol;
+use serde::{Deserialize, Serialize};
 
 sol! {
     /// Trade struct for opening positions
@@ -24,13 +25,13 @@ sol! {
         uint32 builderFee;     // Fee in basis points
     }
 
-    /// Order type enum
-    /// 0 = MARKET
-    /// 1 = LIMIT_OPEN
-    /// 2 = STOP_OPEN
+    // Order type enum
+    // 0 = MARKET
+    // 1 = LIMIT_OPEN
+    // 2 = STOP_OPEN
 
     /// Trading contract interface
-    #[sol(rpc)]
+    #[sol(rpc, abi)]
     interface ITrading {
         /// Open a new trade
         function openTrade(
@@ -75,12 +76,33 @@ sol! {
             bytes calldata call_data
         ) external returns (bytes memory);
 
+        /// Get the delegate approved to act for a trader (zero if none)
+        function delegations(address trader) external view returns (address);
+
         /// Get max allowed collateral
         function maxAllowedCollateral() external view returns (uint256);
 
         /// Check if paused
         function isPaused() external view returns (bool);
 
+        /// Take profit updated event
+        event TpUpdated(
+            uint256 indexed tradeId,
+            address indexed trader,
+            uint16 indexed pairIndex,
+            uint8 index,
+            uint192 newTp
+        );
+
+        /// Stop loss updated event
+        event SlUpdated(
+            uint256 indexed tradeId,
+            address indexed trader,
+            uint16 indexed pairIndex,
+            uint8 index,
+            uint192 newSl
+        );
+
         /// Price requested event
         event PriceRequested(
             uint256 indexed orderId,
@@ -95,7 +117,8 @@ sol! {
 }
 
 /// Order type for opening trades
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 #[repr(u8)]
 pub enum OrderType {
     /// Market order - execute immediately at current price
@@ -58,5 +58,11 @@ sol! {
 
         /// Check if a trade is open (by checking if collateral > 0)
         function hasOpenTrade(address trader, uint16 pairIndex, uint8 index) external view returns (bool);
+
+        /// Get count of pending limit/stop orders for a trader on a specific pair
+        function openLimitOrdersCount(address trader, uint16 pairIndex) external view returns (uint8);
+
+        /// Check if a limit/stop order is pending at an index
+        function hasOpenLimitOrder(address trader, uint16 pairIndex, uint8 index) external view returns (bool);
     }
 }
@@ -4,7 +4,7 @@ use alloy::sol;
 
 sol! {
     /// Standard ERC20 interface
-    #[sol(rpc)]
+    #[sol(rpc, abi)]
     interface IERC20 {
         /// Returns the name of the token
         function name() external view returns (string memory);
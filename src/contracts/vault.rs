@@ -15,7 +15,7 @@ sol! {
     }
 
     /// ERC-4626 Vault interface with Ostium extensions
-    #[sol(rpc)]
+    #[sol(rpc, abi)]
     interface IOstiumVault {
         // ERC-4626 Standard Functions
 
@@ -1,5 +1,251 @@
 //! Error types for the Ostium SDK
 //!
-//! Uses `eyre` for ergonomic error handling with context.
+//! Uses `eyre` for ergonomic error handling with context. Errors that callers
+//! may want to branch on are attached as an [`OstiumError`] and can be
+//! recovered with `report.downcast_ref::<OstiumError>()`.
 
 pub use eyre::{eyre, Context, Report, Result};
+
+use alloy::primitives::U256;
+use std::fmt;
+
+/// Typed SDK error attached to an `eyre::Report`
+#[derive(Debug, Clone, PartialEq)]
+pub enum OstiumError {
+    /// Failed before any transaction was broadcast; safe to retry
+    RetrySafe(String),
+    /// Failed after the transaction may have been broadcast (e.g. a timeout
+    /// after submission); retrying may submit a duplicate
+    RetryUnsafe(String),
+    /// A pair or trade index is outside the protocol's limits
+    InvalidIndex(String),
+    /// An HTTP API couldn't be reached (connect failure or timeout)
+    Network(String),
+    /// An HTTP API answered with a non-success status
+    HttpStatus {
+        /// HTTP status code
+        status: u16,
+        /// Response body
+        message: String,
+    },
+    /// A response body couldn't be decoded
+    Decode(String),
+    /// An API rejected the access token as expired or invalid (HTTP 401)
+    AuthExpired(String),
+    /// The signer couldn't sign the transaction, so nothing was broadcast
+    SigningFailed(String),
+    /// The signer or chain rejected a submitted transaction, with the reason
+    /// reported (e.g. Fordefi's push error or the revert reason)
+    TransactionReverted(String),
+    /// More OLP shares were requested than the owner holds
+    InsufficientShares {
+        /// Owner's share balance (raw, OLP token decimals)
+        available: U256,
+    },
+}
+
+impl OstiumError {
+    /// Whether the failed operation can be retried without risking a duplicate
+    ///
+    /// An outermost [`OstiumError::AuthExpired`] counts as safe: the API
+    /// rejected the request before acting on it. Where a 401 could follow a
+    /// submission (e.g. while polling), it is wrapped in
+    /// [`OstiumError::RetryUnsafe`] instead. [`OstiumError::SigningFailed`] is
+    /// safe too, since an unsigned transaction can't be broadcast.
+    pub fn is_retry_safe(&self) -> bool {
+        matches!(
+            self,
+            OstiumError::RetrySafe(_) | OstiumError::AuthExpired(_) | OstiumError::SigningFailed(_)
+        )
+    }
+
+    /// Whether the failure is likely temporary: network errors, rate limits
+    /// (429), and server errors (5xx)
+    pub fn is_transient(&self) -> bool {
+        match self {
+            OstiumError::Network(_) => true,
+            OstiumError::HttpStatus { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for OstiumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OstiumError::RetrySafe(msg)
+            | OstiumError::RetryUnsafe(msg)
+            | OstiumError::InvalidIndex(msg)
+            | OstiumError::Network(msg)
+            | OstiumError::Decode(msg) => write!(f, "{}", msg),
+            OstiumError::AuthExpired(msg) => write!(f, "access token expired: {}", msg),
+            OstiumError::SigningFailed(msg) => write!(f, "signing failed: {}", msg),
+            OstiumError::TransactionReverted(msg) => write!(f, "transaction reverted: {}", msg),
+            OstiumError::HttpStatus { status, message } => write!(f, "{} - {}", status, message),
+            OstiumError::InsufficientShares { available } => {
+                write!(f, "insufficient OLP shares: {} available", available)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OstiumError {}
+
+impl From<reqwest::Error> for OstiumError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            OstiumError::Decode(err.to_string())
+        } else if let Some(status) = err.status() {
+            OstiumError::HttpStatus {
+                status: status.as_u16(),
+                message: err.to_string(),
+            }
+        } else {
+            OstiumError::Network(err.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for OstiumError {
+    fn from(err: serde_json::Error) -> Self {
+        OstiumError::Decode(err.to_string())
+    }
+}
+
+/// Attach a typed [`OstiumError`] to low-level HTTP and JSON failures along
+/// with a context message
+pub(crate) trait TypedContext<T> {
+    fn typed_context(self, msg: &str) -> Result<T>;
+}
+
+impl<T, E: Into<OstiumError>> TypedContext<T> for std::result::Result<T, E> {
+    fn typed_context(self, msg: &str) -> Result<T> {
+        self.map_err(|e| Report::new(e.into()).wrap_err(msg.to_string()))
+    }
+}
+
+/// Error for a non-success HTTP response, with the body as message
+///
+/// A 401 is reported as [`OstiumError::AuthExpired`].
+pub(crate) fn http_status_error(status: reqwest::StatusCode, body: String) -> Report {
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Report::new(OstiumError::AuthExpired(body));
+    }
+    Report::new(OstiumError::HttpStatus {
+        status: status.as_u16(),
+        message: body,
+    })
+}
+
+/// Whether an error is known to be safe to retry
+///
+/// Only errors explicitly classified as [`OstiumError::RetrySafe`] (or a
+/// pre-submission [`OstiumError::AuthExpired`]) return true.
+/// Anything unclassified is treated as unsafe, so automatic retry loops should
+/// only retry when this returns true.
+pub fn is_retry_safe(err: &Report) -> bool {
+    err.downcast_ref::<OstiumError>()
+        .is_some_and(OstiumError::is_retry_safe)
+}
+
+/// Attach a retry-safe classification to a pre-broadcast failure
+pub(crate) fn retry_safe(err: Report, msg: &str) -> Report {
+    err.wrap_err(OstiumError::RetrySafe(msg.to_string()))
+}
+
+/// Add context to a send failure, classifying it as retry-unsafe unless the
+/// signer already classified it as retry-safe or retry-unsafe, reported an
+/// expired token on create, or reported a final
+/// [`OstiumError::SigningFailed`] or [`OstiumError::TransactionReverted`]
+/// outcome
+///
+/// Other typed errors (e.g. a transient network failure while polling a
+/// transaction that already exists) don't say whether anything was
+/// broadcast, so they are treated as retry-unsafe too.
+pub(crate) fn classify_send_error(err: Report, msg: &str) -> Report {
+    let classified = matches!(
+        err.downcast_ref::<OstiumError>(),
+        Some(
+            OstiumError::RetrySafe(_)
+                | OstiumError::RetryUnsafe(_)
+                | OstiumError::AuthExpired(_)
+                | OstiumError::SigningFailed(_)
+                | OstiumError::TransactionReverted(_)
+        )
+    );
+    if classified {
+        err.wrap_err(msg.to_string())
+    } else {
+        err.wrap_err(OstiumError::RetryUnsafe(msg.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_classification_survives_context() {
+        let safe = retry_safe(eyre!("allowance read failed"), "Failed to check allowance");
+        assert!(is_retry_safe(&safe));
+
+        // A signer-classified error keeps its classification under more context
+        let signer_err = eyre!("400 Bad Request").wrap_err(OstiumError::RetrySafe(
+            "Fordefi rejected transaction".into(),
+        ));
+        assert!(is_retry_safe(&classify_send_error(
+            signer_err,
+            "Failed to place order"
+        )));
+
+        // A transient poll failure doesn't say whether anything was broadcast
+        let poll_err = Report::new(OstiumError::Network("connection reset".into()))
+            .wrap_err("Failed to get transaction status");
+        let classified = classify_send_error(poll_err, "Failed to place order");
+        assert!(!is_retry_safe(&classified));
+        let typed = classified.downcast_ref::<OstiumError>().unwrap();
+        assert!(matches!(typed, OstiumError::RetryUnsafe(_)));
+        assert!(!typed.is_transient());
+
+        // Unclassified send errors are conservatively unsafe
+        let unknown = classify_send_error(eyre!("timed out"), "Failed to place order");
+        assert!(!is_retry_safe(&unknown));
+        assert!(matches!(
+            unknown.downcast_ref::<OstiumError>(),
+            Some(OstiumError::RetryUnsafe(_))
+        ));
+    }
+
+    #[test]
+    fn test_typed_http_errors() {
+        let err = serde_json::from_str::<Vec<u64>>("{").unwrap_err();
+        let report = Err::<(), _>(err)
+            .typed_context("Failed to parse price response")
+            .unwrap_err();
+        assert!(matches!(
+            report.downcast_ref::<OstiumError>(),
+            Some(OstiumError::Decode(_))
+        ));
+
+        let report = http_status_error(reqwest::StatusCode::TOO_MANY_REQUESTS, "slow down".into())
+            .wrap_err("Subgraph query failed");
+        let typed = report.downcast_ref::<OstiumError>().unwrap();
+        assert!(typed.is_transient());
+        assert_eq!(
+            format!("{:#}", report),
+            "Subgraph query failed: 429 - slow down"
+        );
+
+        let expired = http_status_error(reqwest::StatusCode::UNAUTHORIZED, "jwt expired".into());
+        assert!(matches!(
+            expired.downcast_ref::<OstiumError>(),
+            Some(OstiumError::AuthExpired(_))
+        ));
+
+        let not_found = OstiumError::HttpStatus {
+            status: 404,
+            message: String::new(),
+        };
+        assert!(!not_found.is_transient());
+    }
+}
@@ -8,6 +8,8 @@
 //! - **Trading**: Open/close BTC perpetual positions with configurable leverage
 //! - **OLP Vault**: Deposit USDC, request withdrawals, approve auto-withdraw
 //! - **Fordefi MPC**: Secure institutional-grade signing via Fordefi API
+//! - **Blocking API**: `BlockingOstiumClient` for synchronous callers (`blocking` feature)
+//! - **Config files**: `OstiumClient::from_config_file` for TOML deployments (`config-file` feature)
 //!
 //! # Example
 //!
@@ -36,19 +38,39 @@
 //! }
 //! ```
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod cache;
 pub mod client;
 pub mod config;
+#[cfg(feature = "config-file")]
+pub mod config_file;
 pub mod constants;
 pub mod contracts;
 pub mod error;
 pub mod price;
 pub mod signer;
+pub mod subgraph;
 pub mod types;
 
 // Re-export main types for convenience
-pub use client::OstiumClient;
-pub use config::NetworkConfig;
-pub use error::{eyre, Context, Report, Result};
-pub use price::{get_btc_price, get_eth_price, get_price};
-pub use signer::{FordefiSigner, TransactionSigner, TxRequest};
-pub use types::{CloseTradeParams, DepositParams, PlaceOrderParams, Position, VaultEpoch, VaultPosition};
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingOstiumClient;
+pub use client::{OstiumClient, PendingTransaction};
+pub use config::{HttpConfig, Network, NetworkConfig, NetworkConfigBuilder};
+pub use error::{eyre, Context, OstiumError, Report, Result};
+pub use price::{
+    get_all_prices, get_btc_price, get_cross_price, get_eth_price, get_inverse_price, get_price,
+    get_price_data, get_price_with_http_config, get_prices, AlertCondition, PriceAlert, PriceData,
+};
+pub use signer::{FordefiSigner, PollConfig, SignerKind, TransactionSigner, TxRequest};
+pub use subgraph::SubgraphClient;
+pub use types::{
+    diff_positions, realized_pnl, AccountValue, ApprovalMode, CloseQuote, CloseResult,
+    CloseTradeParams, CollateralPreview, ConnectivityReport, DecodedCall, DepositParams,
+    EndpointStatus, LifecycleEvent, LifecycleEventKind, OpenOrder, OstiumEvent, PairInfo,
+    PlaceOrderParams, PnlBreakdown, Position, PositionDiff, PositionExposure, PositionScanResult,
+    PositionSnapshot, PositionSource, ProtocolLimits, ReceiptSummary, RedeemParams, SlippageMode,
+    SlippageRecommendation, TokenMetadata, TradeEvent, TradeFees, TradeLifecycle, TradeTicket,
+    VaultEpoch, VaultPosition, VaultUtilization,
+};
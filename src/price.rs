@@ -1,12 +1,16 @@
 //! Price fetching from Ostium metadata backend
 
-use eyre::{Context, Result};
+use crate::config::HttpConfig;
+use crate::error::{http_status_error, OstiumError, TypedContext};
+use eyre::{Context, Report, Result};
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
 
 const OSTIUM_PRICE_API: &str = "https://metadata-backend.ostium.io/PricePublish/latest-prices";
 
 /// Price data from Ostium API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct PriceData {
     pub from: String,
     pub to: String,
@@ -19,10 +23,306 @@ pub struct PriceData {
     pub is_day_trading_closed: bool,
 }
 
+impl PriceData {
+    /// Bid/ask spread as a percentage of the mid price
+    pub fn spread_percent(&self) -> f64 {
+        if self.mid <= 0.0 {
+            return 0.0;
+        }
+        (self.ask - self.bid) / self.mid * 100.0
+    }
+
+    /// Quote for the inverse pair (e.g. USD/EUR from EUR/USD)
+    ///
+    /// The bid and ask swap sides: selling the inverse pair means buying the
+    /// listed one at its ask.
+    pub fn inverse(&self) -> Result<PriceData> {
+        eyre::ensure!(
+            self.bid > 0.0 && self.mid > 0.0 && self.ask > 0.0,
+            "Can't invert {}/{} with a non-positive price",
+            self.from,
+            self.to
+        );
+        Ok(PriceData {
+            from: self.to.clone(),
+            to: self.from.clone(),
+            bid: 1.0 / self.ask,
+            mid: 1.0 / self.mid,
+            ask: 1.0 / self.bid,
+            is_market_open: self.is_market_open,
+            is_day_trading_closed: self.is_day_trading_closed,
+        })
+    }
+
+    /// Cross quote `a.from/b.from` from two legs quoted in the same currency
+    ///
+    /// E.g. EUR/USD and GBP/USD give EUR/GBP. The cross is open only if both
+    /// legs are, and day trading is closed if either leg's is.
+    pub fn cross(a: &PriceData, b: &PriceData) -> Result<PriceData> {
+        eyre::ensure!(
+            a.to == b.to,
+            "Cross legs {}/{} and {}/{} aren't quoted in the same currency",
+            a.from,
+            a.to,
+            b.from,
+            b.to
+        );
+        eyre::ensure!(
+            b.bid > 0.0 && b.mid > 0.0 && b.ask > 0.0,
+            "Can't cross through {}/{} with a non-positive price",
+            b.from,
+            b.to
+        );
+        Ok(PriceData {
+            from: a.from.clone(),
+            to: b.from.clone(),
+            bid: a.bid / b.ask,
+            mid: a.mid / b.mid,
+            ask: a.ask / b.bid,
+            is_market_open: a.is_market_open && b.is_market_open,
+            is_day_trading_closed: a.is_day_trading_closed || b.is_day_trading_closed,
+        })
+    }
+}
+
+/// Find `base/quote` among listed prices, inverting `quote/base` if only that is listed
+fn find_leg(prices: &[PriceData], base: &str, quote: &str) -> Result<PriceData> {
+    if let Some(price) = prices.iter().find(|p| p.from == base && p.to == quote) {
+        return Ok(price.clone());
+    }
+    match prices.iter().find(|p| p.from == quote && p.to == base) {
+        Some(price) => price.inverse(),
+        None => eyre::bail!(
+            "No listed pair for {}/{} or {}/{}",
+            base,
+            quote,
+            quote,
+            base
+        ),
+    }
+}
+
+/// Price condition watched by a [`PriceAlert`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertCondition {
+    /// Mid price at or above the level
+    Above(f64),
+    /// Mid price at or below the level
+    Below(f64),
+    /// Mid price moves from below the level to at or above it
+    CrossesUp(f64),
+    /// Mid price moves from above the level to at or below it
+    CrossesDown(f64),
+}
+
+/// One-shot price alert with debounce
+///
+/// Feed it prices with [`PriceAlert::observe`]; it fires once the condition
+/// has held for `confirmations` consecutive samples, so a single tick
+/// flapping across the level doesn't trigger it. Crossing conditions only
+/// fire after the price has been seen on the near side of the level.
+#[derive(Debug, Clone)]
+pub struct PriceAlert {
+    /// Condition to watch
+    pub condition: AlertCondition,
+    /// Consecutive samples the condition must hold before firing
+    pub confirmations: u32,
+    /// Delay between price polls
+    pub interval: Duration,
+    armed: bool,
+    streak: u32,
+}
+
+impl PriceAlert {
+    /// Create an alert that fires on two consecutive samples, polling every 2 seconds
+    pub fn new(condition: AlertCondition) -> Self {
+        Self {
+            condition,
+            confirmations: 2,
+            interval: Duration::from_secs(2),
+            armed: false,
+            streak: 0,
+        }
+    }
+
+    /// Set the consecutive samples required to fire (minimum 1)
+    pub fn with_confirmations(mut self, confirmations: u32) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+
+    /// Set the delay between price polls
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Record a price sample, returning true when the alert fires
+    pub fn observe(&mut self, price: f64) -> bool {
+        let (on_side, needs_cross) = match self.condition {
+            AlertCondition::Above(level) => (price >= level, false),
+            AlertCondition::Below(level) => (price <= level, false),
+            AlertCondition::CrossesUp(level) => (price >= level, true),
+            AlertCondition::CrossesDown(level) => (price <= level, true),
+        };
+
+        if !on_side {
+            self.armed = true;
+            self.streak = 0;
+            return false;
+        }
+        if needs_cross && !self.armed {
+            return false;
+        }
+
+        self.streak += 1;
+        self.streak >= self.confirmations
+    }
+
+    /// Poll the price feed until the alert fires, then call `on_fire` once
+    ///
+    /// Transient price feed errors are logged and the poll continues.
+    ///
+    /// # Returns
+    ///
+    /// The mid price that fired the alert
+    pub async fn watch(
+        mut self,
+        from: &str,
+        to: &str,
+        http: &HttpConfig,
+        on_fire: impl FnOnce(f64),
+    ) -> Result<f64> {
+        loop {
+            match get_price_with_http_config(from, to, http).await {
+                Ok(price) if self.observe(price) => {
+                    on_fire(price);
+                    return Ok(price);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Price alert poll for {}/{} failed: {:#}", from, to, e),
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
 /// Fetch the current price for a trading pair
 pub async fn get_price(from: &str, to: &str) -> Result<f64> {
-    let client = reqwest::Client::builder()
-        .user_agent("OstiumRustSDK/0.1.0")
+    get_price_with_http_config(from, to, &HttpConfig::default()).await
+}
+
+/// Fetch the current price for a trading pair using custom HTTP settings
+pub async fn get_price_with_http_config(from: &str, to: &str, http: &HttpConfig) -> Result<f64> {
+    Ok(get_price_data_with_http_config(from, to, http).await?.mid)
+}
+
+/// Fetch the full quote (bid/mid/ask and market status) for a trading pair
+pub async fn get_price_data(from: &str, to: &str) -> Result<PriceData> {
+    get_price_data_with_http_config(from, to, &HttpConfig::default()).await
+}
+
+/// Fetch the full quote for a trading pair using custom HTTP settings
+pub async fn get_price_data_with_http_config(
+    from: &str,
+    to: &str,
+    http: &HttpConfig,
+) -> Result<PriceData> {
+    let prices = get_all_prices_with_http_config(http).await?;
+
+    prices
+        .into_iter()
+        .find(|price| price.from == from && price.to == to)
+        .ok_or_else(|| eyre::eyre!("No price found for {}/{}", from, to))
+}
+
+/// Fetch mid prices for several pairs with a single request
+///
+/// Errors listing every requested pair that isn't quoted.
+pub async fn get_prices(pairs: &[(String, String)]) -> Result<HashMap<(String, String), f64>> {
+    get_prices_with_http_config(pairs, &HttpConfig::default()).await
+}
+
+/// Fetch mid prices for several pairs using custom HTTP settings
+pub async fn get_prices_with_http_config(
+    pairs: &[(String, String)],
+    http: &HttpConfig,
+) -> Result<HashMap<(String, String), f64>> {
+    let prices = get_all_prices_with_http_config(http).await?;
+    select_prices(&prices, pairs)
+}
+
+/// Pick the requested pairs' mid prices out of the full price list
+fn select_prices(
+    prices: &[PriceData],
+    pairs: &[(String, String)],
+) -> Result<HashMap<(String, String), f64>> {
+    let mut selected = HashMap::with_capacity(pairs.len());
+    let mut missing = Vec::new();
+    for (from, to) in pairs {
+        match prices.iter().find(|p| &p.from == from && &p.to == to) {
+            Some(price) => {
+                selected.insert((from.clone(), to.clone()), price.mid);
+            }
+            None => missing.push(format!("{}/{}", from, to)),
+        }
+    }
+    eyre::ensure!(
+        missing.is_empty(),
+        "No price found for {}",
+        missing.join(", ")
+    );
+    Ok(selected)
+}
+
+/// Fetch the inverse of a listed pair, e.g. `get_inverse_price("EUR", "USD")`
+/// for USD/EUR
+pub async fn get_inverse_price(from: &str, to: &str) -> Result<PriceData> {
+    get_inverse_price_with_http_config(from, to, &HttpConfig::default()).await
+}
+
+/// Fetch the inverse of a listed pair using custom HTTP settings
+pub async fn get_inverse_price_with_http_config(
+    from: &str,
+    to: &str,
+    http: &HttpConfig,
+) -> Result<PriceData> {
+    get_price_data_with_http_config(from, to, http)
+        .await?
+        .inverse()
+}
+
+/// Fetch the cross quote `a/b` computed through a common currency, e.g.
+/// `get_cross_price("EUR", "GBP", "USD")` from EUR/USD and GBP/USD
+///
+/// Either leg may be listed the other way round (e.g. USD/JPY for a JPY
+/// cross) and is inverted. The cross is open only if both legs are.
+pub async fn get_cross_price(a: &str, b: &str, via: &str) -> Result<PriceData> {
+    get_cross_price_with_http_config(a, b, via, &HttpConfig::default()).await
+}
+
+/// Fetch a cross quote using custom HTTP settings
+pub async fn get_cross_price_with_http_config(
+    a: &str,
+    b: &str,
+    via: &str,
+    http: &HttpConfig,
+) -> Result<PriceData> {
+    let prices = get_all_prices_with_http_config(http).await?;
+    let a_leg = find_leg(&prices, a, via)?;
+    let b_leg = find_leg(&prices, b, via)?;
+    PriceData::cross(&a_leg, &b_leg)
+}
+
+/// Fetch the latest prices for all pairs
+pub async fn get_all_prices() -> Result<Vec<PriceData>> {
+    get_all_prices_with_http_config(&HttpConfig::default()).await
+}
+
+/// Fetch the latest prices for all pairs using custom HTTP settings
+pub async fn get_all_prices_with_http_config(http: &HttpConfig) -> Result<Vec<PriceData>> {
+    let client = http
+        .client_builder()?
         .build()
         .context("Failed to create HTTP client")?;
 
@@ -30,20 +330,39 @@ pub async fn get_price(from: &str, to: &str) -> Result<f64> {
         .get(OSTIUM_PRICE_API)
         .send()
         .await
-        .context("Failed to fetch prices")?;
+        .typed_context("Failed to fetch prices")?;
 
-    let text = response.text().await.context("Failed to read response body")?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(http_status_error(status, body).wrap_err("Price API request failed"));
+    }
 
-    let prices: Vec<PriceData> = serde_json::from_str(&text)
-        .with_context(|| format!("Failed to parse price response: {}", &text[..text.len().min(200)]))?;
+    let text = response
+        .text()
+        .await
+        .typed_context("Failed to read response body")?;
 
-    for price in prices {
-        if price.from == from && price.to == to {
-            return Ok(price.mid);
-        }
-    }
+    let prices: Vec<PriceData> = serde_json::from_str(&text).map_err(|e| {
+        Report::new(OstiumError::from(e)).wrap_err(format!(
+            "Failed to parse price response: {}",
+            truncate(&text, 200)
+        ))
+    })?;
 
-    eyre::bail!("No price found for {}/{}", from, to)
+    Ok(prices)
+}
+
+/// Truncate to at most `max_bytes` bytes without splitting a UTF-8 character
+fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
 }
 
 /// Get BTC/USD price
@@ -60,6 +379,96 @@ pub async fn get_eth_price() -> Result<f64> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        assert_eq!(truncate("short", 200), "short");
+        assert_eq!(truncate("abcdef", 3), "abc");
+        // "é" is two bytes; cutting after byte 2 would split it
+        assert_eq!(truncate("aé€", 2), "a");
+        assert_eq!(truncate("aé€", 3), "aé");
+        assert_eq!(truncate("€", 0), "");
+    }
+
+    #[test]
+    fn test_price_alert_debounce() {
+        let mut above = PriceAlert::new(AlertCondition::Above(70_000.0));
+        assert!(!above.observe(70_100.0));
+        // Dipping back resets the streak
+        assert!(!above.observe(69_900.0));
+        assert!(!above.observe(70_050.0));
+        assert!(above.observe(70_200.0));
+
+        // Starting above the level doesn't count as crossing up
+        let mut cross = PriceAlert::new(AlertCondition::CrossesUp(70_000.0)).with_confirmations(1);
+        assert!(!cross.observe(71_000.0));
+        assert!(!cross.observe(69_000.0));
+        assert!(cross.observe(70_000.0));
+
+        let mut down = PriceAlert::new(AlertCondition::CrossesDown(100.0)).with_confirmations(1);
+        assert!(!down.observe(99.0));
+        assert!(!down.observe(101.0));
+        assert!(down.observe(99.5));
+    }
+
+    fn quote(from: &str, to: &str, bid: f64, ask: f64, open: bool) -> PriceData {
+        PriceData {
+            from: from.to_string(),
+            to: to.to_string(),
+            bid,
+            mid: (bid + ask) / 2.0,
+            ask,
+            is_market_open: open,
+            is_day_trading_closed: false,
+        }
+    }
+
+    #[test]
+    fn test_inverse_and_cross_prices() {
+        let eur = quote("EUR", "USD", 1.08, 1.10, true);
+        let inverse = eur.inverse().unwrap();
+        assert_eq!((inverse.from.as_str(), inverse.to.as_str()), ("USD", "EUR"));
+        assert!((inverse.bid - 1.0 / 1.10).abs() < 1e-12);
+        assert!((inverse.ask - 1.0 / 1.08).abs() < 1e-12);
+        assert!(quote("EUR", "USD", 0.0, 1.0, true).inverse().is_err());
+
+        let gbp = quote("GBP", "USD", 1.25, 1.27, false);
+        let cross = PriceData::cross(&eur, &gbp).unwrap();
+        assert_eq!((cross.from.as_str(), cross.to.as_str()), ("EUR", "GBP"));
+        assert!((cross.bid - 1.08 / 1.27).abs() < 1e-12);
+        assert!((cross.ask - 1.10 / 1.25).abs() < 1e-12);
+        assert!(!cross.is_market_open);
+        assert!(PriceData::cross(&eur, &inverse).is_err());
+
+        // A leg listed the other way round is inverted
+        let prices = vec![eur.clone(), quote("USD", "JPY", 150.0, 150.2, true)];
+        let jpy = find_leg(&prices, "JPY", "USD").unwrap();
+        assert!((jpy.mid - 1.0 / 150.1).abs() < 1e-12);
+        let eur_jpy = PriceData::cross(&eur, &jpy).unwrap();
+        assert!((eur_jpy.mid - 1.09 * 150.1).abs() < 1e-9);
+        assert!(find_leg(&prices, "XAU", "USD").is_err());
+    }
+
+    #[test]
+    fn test_select_prices() {
+        let prices = vec![
+            quote("BTC", "USD", 99.0, 101.0, true),
+            quote("EUR", "USD", 1.0, 1.2, true),
+        ];
+        let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+
+        let selected = select_prices(&prices, &[pair("BTC", "USD"), pair("EUR", "USD")]).unwrap();
+        assert_eq!(selected[&pair("BTC", "USD")], 100.0);
+        assert!((selected[&pair("EUR", "USD")] - 1.1).abs() < 1e-12);
+
+        let err = select_prices(
+            &prices,
+            &[pair("BTC", "USD"), pair("XAU", "USD"), pair("X", "Y")],
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "No price found for XAU/USD, X/Y");
+        assert!(select_prices(&prices, &[]).unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_btc_price() {
         let price = get_btc_price().await.unwrap();
@@ -5,11 +5,12 @@
 
 mod fordefi;
 
-pub use fordefi::FordefiSigner;
+pub use fordefi::{FordefiSigner, FordefiTxDetails, SubmissionSink, TokenProvider};
 
 use alloy::primitives::{Address, Bytes, TxHash, U256};
 use alloy::rpc::types::TransactionReceipt;
 use eyre::Result;
+use std::time::Duration;
 
 /// Transaction request parameters
 #[derive(Debug, Clone)]
@@ -22,6 +23,10 @@ pub struct TxRequest {
     pub data: Bytes,
     /// Optional gas limit override
     pub gas_limit: Option<u64>,
+    /// Free-form note for the signer's audit trail (e.g. Fordefi console)
+    pub note: Option<String>,
+    /// Business reference (e.g. an internal order id) to tag the transaction with
+    pub external_reference: Option<String>,
 }
 
 impl TxRequest {
@@ -32,6 +37,8 @@ impl TxRequest {
             value: U256::ZERO,
             data: data.into(),
             gas_limit: None,
+            note: None,
+            external_reference: None,
         }
     }
 
@@ -46,6 +53,82 @@ impl TxRequest {
         self.gas_limit = Some(gas_limit);
         self
     }
+
+    /// Attach a note for the signer's audit trail
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// Tag the transaction with a business reference such as an order id
+    pub fn with_external_reference(mut self, reference: impl Into<String>) -> Self {
+        self.external_reference = Some(reference.into());
+        self
+    }
+}
+
+/// Signing backend behind a [`TransactionSigner`]
+///
+/// Lets code that is generic over the signer adapt to the active backend
+/// (e.g. skip local nonce handling for custodial signers) without downcasting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignerKind {
+    /// Private key or mnemonic held in process
+    Local,
+    /// Fordefi MPC wallet
+    Fordefi,
+    /// Cloud key management service
+    Kms,
+    /// Test double
+    Mock,
+    /// Backend that doesn't identify itself
+    #[default]
+    Unknown,
+}
+
+/// Polling limits for waiting on transaction status and receipts
+///
+/// The defaults wait up to 3 minutes for Fordefi to sign and push a
+/// transaction and up to 2 minutes for its receipt, polling every 2 seconds.
+/// Raise the attempt counts on slow approval policies or lagging RPCs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollConfig {
+    /// Delay between polls
+    pub interval: Duration,
+    /// Max polls of the signer's status API before giving up on a tx hash
+    pub max_status_attempts: u32,
+    /// Max receipt polls before giving up on confirmation
+    pub max_receipt_attempts: u32,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            max_status_attempts: 90,
+            max_receipt_attempts: 60,
+        }
+    }
+}
+
+impl PollConfig {
+    /// Set the delay between polls
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the max status polls
+    pub fn with_max_status_attempts(mut self, attempts: u32) -> Self {
+        self.max_status_attempts = attempts;
+        self
+    }
+
+    /// Set the max receipt polls
+    pub fn with_max_receipt_attempts(mut self, attempts: u32) -> Self {
+        self.max_receipt_attempts = attempts;
+        self
+    }
 }
 
 /// Trait for signing and sending EVM transactions
@@ -55,6 +138,11 @@ pub trait TransactionSigner: Send + Sync {
     /// Returns the signer's EVM address
     fn address(&self) -> Address;
 
+    /// Returns the signing backend (defaults to [`SignerKind::Unknown`])
+    fn signer_kind(&self) -> SignerKind {
+        SignerKind::Unknown
+    }
+
     /// Signs and sends a transaction, returning the transaction hash
     fn sign_and_send(
         &self,
@@ -67,6 +155,18 @@ pub trait TransactionSigner: Send + Sync {
         tx_hash: TxHash,
     ) -> impl std::future::Future<Output = Result<TransactionReceipt>> + Send;
 
+    /// Waits for a receipt using the given polling limits instead of the signer's own
+    ///
+    /// Signers without configurable polling fall back to `wait_for_receipt`.
+    fn wait_for_receipt_with(
+        &self,
+        tx_hash: TxHash,
+        poll: &PollConfig,
+    ) -> impl std::future::Future<Output = Result<TransactionReceipt>> + Send {
+        let _ = poll;
+        self.wait_for_receipt(tx_hash)
+    }
+
     /// Gets the native token balance (ETH on Arbitrum)
     fn get_balance(&self) -> impl std::future::Future<Output = Result<U256>> + Send;
 }
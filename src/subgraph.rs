@@ -0,0 +1,362 @@
+//! Ostium subgraph client for protocol data not exposed by the contracts we bind
+
+use crate::config::HttpConfig;
+use crate::error::{http_status_error, TypedContext};
+use alloy::primitives::{Address, U256};
+use eyre::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use std::time::Duration;
+
+const META_QUERY: &str = "query getMeta { _meta { block { number } } }";
+
+const PAIRS_QUERY: &str = r#"
+query getPairs {
+  pairs(first: 1000) {
+    id
+    from
+    to
+    longOI
+    shortOI
+    maxOI
+    makerFeeP
+    takerFeeP
+    maxLeverage
+    group {
+      name
+      minLeverage
+      maxLeverage
+    }
+    fee {
+      minLevPos
+    }
+  }
+}
+"#;
+
+const TRADE_ORDERS_QUERY: &str = r#"
+query getTradeOrders($trader: Bytes!, $pair: String!, $index: BigInt!) {
+  orders(
+    first: 1000
+    where: { trader: $trader, pair: $pair, index: $index, isPending: false }
+    orderBy: executedAt
+    orderDirection: desc
+  ) {
+    id
+    orderAction
+    price
+    closePercent
+    collateral
+    leverage
+    isCancelled
+    executedAt
+    executedTx
+  }
+}
+"#;
+
+const ORDER_QUERY: &str = r#"
+query getOrder($id: ID!) {
+  order(id: $id) {
+    id
+    orderAction
+    price
+    closePercent
+    collateral
+    leverage
+    isCancelled
+    executedAt
+    executedTx
+    amountSentToTrader
+  }
+}
+"#;
+
+const CLOSED_ORDERS_QUERY: &str = r#"
+query getClosedOrders($trader: Bytes!, $since: BigInt!) {
+  orders(
+    first: 1000
+    where: {
+      trader: $trader
+      isPending: false
+      isCancelled: false
+      orderAction_not: "Open"
+      executedAt_gte: $since
+    }
+    orderBy: executedAt
+    orderDirection: desc
+  ) {
+    id
+    orderAction
+    price
+    closePercent
+    collateral
+    leverage
+    isCancelled
+    executedAt
+    executedTx
+    amountSentToTrader
+  }
+}
+"#;
+
+/// Client for the Ostium GraphQL subgraph
+#[derive(Debug, Clone)]
+pub struct SubgraphClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+/// Raw pair entity from the subgraph (numeric values are scaled integers as strings)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphPair {
+    /// Pair index
+    pub id: String,
+    /// Base asset symbol (e.g. "BTC")
+    pub from: String,
+    /// Quote asset symbol (e.g. "USD")
+    pub to: String,
+    /// Long open interest (18 decimals)
+    #[serde(rename = "longOI")]
+    pub long_oi: String,
+    /// Short open interest (18 decimals)
+    #[serde(rename = "shortOI")]
+    pub short_oi: String,
+    /// Max open interest per side (6 decimals)
+    #[serde(rename = "maxOI")]
+    pub max_oi: String,
+    /// Maker fee percentage (6 decimals)
+    pub maker_fee_p: String,
+    /// Taker fee percentage (6 decimals)
+    pub taker_fee_p: String,
+    /// Pair-level max leverage override (2 decimals, 0 if unset)
+    #[serde(default)]
+    pub max_leverage: Option<String>,
+    /// Asset group the pair belongs to
+    pub group: SubgraphPairGroup,
+    /// Fee parameters
+    pub fee: SubgraphPairFee,
+}
+
+/// Asset group of a pair (crypto, forex, commodities, ...)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphPairGroup {
+    /// Group name
+    pub name: String,
+    /// Min leverage (2 decimals)
+    pub min_leverage: String,
+    /// Max leverage (2 decimals)
+    pub max_leverage: String,
+}
+
+/// Fee parameters of a pair
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphPairFee {
+    /// Minimum leveraged position size in USDC (6 decimals)
+    pub min_lev_pos: String,
+}
+
+/// Executed (or cancelled) order entity from the subgraph
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphOrder {
+    /// Order ID
+    pub id: String,
+    /// Order action (e.g. "Open", "Close", "TakeProfit", "StopLoss", "Liquidation")
+    pub order_action: String,
+    /// Execution price (18 decimals)
+    pub price: String,
+    /// Percentage of the trade closed (2 decimals, 10000 = 100%)
+    #[serde(default)]
+    pub close_percent: Option<String>,
+    /// Collateral (6 decimals)
+    pub collateral: String,
+    /// Leverage (2 decimals)
+    pub leverage: String,
+    /// Whether the order was cancelled instead of executed
+    #[serde(default)]
+    pub is_cancelled: bool,
+    /// Unix timestamp of execution
+    pub executed_at: String,
+    /// Execution transaction hash
+    #[serde(default)]
+    pub executed_tx: Option<String>,
+    /// USDC paid to the trader by a close (6 decimals), if queried
+    #[serde(default)]
+    pub amount_sent_to_trader: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdersData {
+    orders: Vec<SubgraphOrder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderData {
+    order: Option<SubgraphOrder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetaData {
+    #[serde(rename = "_meta")]
+    meta: Meta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Meta {
+    block: MetaBlock,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetaBlock {
+    number: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PairsData {
+    pairs: Vec<SubgraphPair>,
+}
+
+impl SubgraphClient {
+    /// Create a subgraph client for the given endpoint
+    pub fn new(url: impl Into<String>, http: &HttpConfig) -> Result<Self> {
+        let client = http
+            .client_builder()?
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            url: url.into(),
+        })
+    }
+
+    /// Get all trading pairs
+    pub async fn get_pairs(&self) -> Result<Vec<SubgraphPair>> {
+        let data: PairsData = self.query(PAIRS_QUERY, json!({})).await?;
+        Ok(data.pairs)
+    }
+
+    /// Get a single trading pair by index
+    pub async fn get_pair(&self, pair_index: u16) -> Result<SubgraphPair> {
+        let id = pair_index.to_string();
+        self.get_pairs()
+            .await?
+            .into_iter()
+            .find(|pair| pair.id == id)
+            .ok_or_else(|| eyre::eyre!("Pair {} not found in subgraph", pair_index))
+    }
+
+    /// Get the executed and cancelled orders of one trade slot, oldest first
+    ///
+    /// A `(pair_index, trade_index)` slot is reused once a trade fully closes,
+    /// so the result can span several trades. Only the latest 1000 orders are
+    /// returned, so the newest trade is always complete.
+    pub async fn get_trade_orders(
+        &self,
+        trader: Address,
+        pair_index: u16,
+        trade_index: u8,
+    ) -> Result<Vec<SubgraphOrder>> {
+        let variables = json!({
+            "trader": format!("{:?}", trader).to_lowercase(),
+            "pair": pair_index.to_string(),
+            "index": trade_index.to_string(),
+        });
+        let mut data: OrdersData = self.query(TRADE_ORDERS_QUERY, variables).await?;
+        data.orders.reverse();
+        Ok(data.orders)
+    }
+
+    /// Get one order by its on-chain order ID
+    ///
+    /// Returns `None` until the subgraph has indexed the order. A pending
+    /// order has neither `executed_tx` set nor `is_cancelled`.
+    pub async fn get_order(&self, order_id: U256) -> Result<Option<SubgraphOrder>> {
+        let variables = json!({ "id": order_id.to_string() });
+        let data: OrderData = self.query(ORDER_QUERY, variables).await?;
+        Ok(data.order)
+    }
+
+    /// Get a trader's executed close, TP/SL and liquidation orders, newest first
+    ///
+    /// Only orders executed at or after `since` (Unix seconds) are returned,
+    /// up to the latest 1000.
+    pub async fn get_closed_orders(
+        &self,
+        trader: Address,
+        since: u64,
+    ) -> Result<Vec<SubgraphOrder>> {
+        let variables = json!({
+            "trader": format!("{:?}", trader).to_lowercase(),
+            "since": since.to_string(),
+        });
+        let data: OrdersData = self.query(CLOSED_ORDERS_QUERY, variables).await?;
+        Ok(data.orders)
+    }
+
+    /// Get the latest block number the subgraph has indexed
+    pub async fn indexed_block(&self) -> Result<u64> {
+        let data: MetaData = self.query(META_QUERY, json!({})).await?;
+        Ok(data.meta.block.number)
+    }
+
+    /// Run a custom GraphQL query against the subgraph
+    ///
+    /// Deserializes the response's `data` field into `T`. GraphQL errors in the
+    /// response are collected into a single error, as are HTTP failures.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - GraphQL query document
+    /// * `variables` - Query variables as a JSON object (use `json!({})` for none)
+    pub async fn query<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<T> {
+        let resp = self
+            .client
+            .post(&self.url)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .typed_context("Failed to query subgraph")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(http_status_error(status, body).wrap_err("Subgraph query failed"));
+        }
+
+        let response: GraphQlResponse<T> = resp
+            .json()
+            .await
+            .typed_context("Failed to parse subgraph response")?;
+
+        if !response.errors.is_empty() {
+            let messages: Vec<_> = response.errors.into_iter().map(|e| e.message).collect();
+            eyre::bail!("Subgraph returned errors: {}", messages.join("; "));
+        }
+
+        response
+            .data
+            .ok_or_else(|| eyre::eyre!("Subgraph response contained no data"))
+    }
+}
@@ -0,0 +1,126 @@
+//! Account-level types for user-facing API
+
+use super::trade::Position;
+
+/// Total account value with a per-component breakdown (all values in USDC)
+#[derive(Debug, Clone)]
+pub struct AccountValue {
+    /// Free USDC in the wallet
+    pub free_usdc: f64,
+    /// Net value of open positions (collateral + unrealized PnL - close fees)
+    pub positions_value: f64,
+    /// Value of OLP vault shares
+    pub olp_value: f64,
+    /// Value of shares with pending withdrawal requests (included in `olp_value`)
+    pub pending_withdrawals_value: f64,
+    /// Total account value
+    pub total: f64,
+}
+
+impl AccountValue {
+    /// Create from components, computing the total
+    pub fn new(
+        free_usdc: f64,
+        positions_value: f64,
+        olp_value: f64,
+        pending_withdrawals_value: f64,
+    ) -> Self {
+        Self {
+            free_usdc,
+            positions_value,
+            olp_value,
+            pending_withdrawals_value,
+            total: free_usdc + positions_value + olp_value,
+        }
+    }
+}
+
+/// Realized, unrealized and net PnL (all values in USDC)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PnlBreakdown {
+    /// PnL realized by closed trades
+    pub realized: f64,
+    /// PnL of open positions at the current price
+    pub unrealized: f64,
+    /// Realized plus unrealized
+    pub net: f64,
+}
+
+impl PnlBreakdown {
+    /// Create from realized and unrealized PnL, computing the net
+    pub fn new(realized: f64, unrealized: f64) -> Self {
+        Self {
+            realized,
+            unrealized,
+            net: realized + unrealized,
+        }
+    }
+}
+
+/// Collateral and notional exposure across open positions (all values in USDC)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PositionExposure {
+    /// Collateral locked in open positions
+    pub total_collateral: f64,
+    /// Notional (collateral * leverage) of long positions
+    pub long_notional: f64,
+    /// Notional (collateral * leverage) of short positions
+    pub short_notional: f64,
+}
+
+impl PositionExposure {
+    /// Aggregate exposure over a set of positions
+    pub fn from_positions(positions: &[Position]) -> Self {
+        positions
+            .iter()
+            .fold(Self::default(), |mut exposure, position| {
+                exposure.total_collateral += position.collateral;
+                if position.is_long {
+                    exposure.long_notional += position.notional();
+                } else {
+                    exposure.short_notional += position.notional();
+                }
+                exposure
+            })
+    }
+
+    /// Total notional regardless of direction
+    pub fn gross_notional(&self) -> f64 {
+        self.long_notional + self.short_notional
+    }
+
+    /// Long minus short notional (positive when net long)
+    pub fn net_notional(&self) -> f64 {
+        self.long_notional - self.short_notional
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::trade::tests::test_position;
+
+    #[test]
+    fn test_position_exposure() {
+        let position = |collateral, leverage, is_long| Position {
+            collateral,
+            leverage,
+            ..test_position(is_long)
+        };
+
+        let exposure = PositionExposure::from_positions(&[
+            position(100.0, 10.0, true),
+            position(50.0, 5.0, false),
+            position(20.0, 2.0, true),
+        ]);
+        assert_eq!(exposure.total_collateral, 170.0);
+        assert_eq!(exposure.long_notional, 1_040.0);
+        assert_eq!(exposure.short_notional, 250.0);
+        assert_eq!(exposure.gross_notional(), 1_290.0);
+        assert_eq!(exposure.net_notional(), 790.0);
+        assert_eq!(
+            PositionExposure::from_positions(&[]),
+            PositionExposure::default()
+        );
+    }
+}
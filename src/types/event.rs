@@ -0,0 +1,125 @@
+//! Protocol event types for user-facing API
+
+use alloy::primitives::{Address, TxHash, U256};
+
+/// Trade lifecycle event emitted by the Trading contract for a trader
+#[derive(Debug, Clone)]
+pub enum TradeEvent {
+    /// An open order was submitted and is waiting for the oracle price
+    OpenRequested(PriceRequest),
+    /// A close order was submitted and is waiting for the oracle price
+    CloseRequested(PriceRequest),
+}
+
+/// Details of a `PriceRequested` event
+#[derive(Debug, Clone)]
+pub struct PriceRequest {
+    /// Oracle order ID
+    pub order_id: U256,
+    /// Trading pair index
+    pub pair_index: u16,
+    /// Order type (0 = market, 1 = limit, 2 = stop)
+    pub order_type: u8,
+    /// Block timestamp of the request
+    pub timestamp: u64,
+    /// Transaction that emitted the event
+    pub tx_hash: Option<TxHash>,
+    /// Block the event was emitted in
+    pub block_number: Option<u64>,
+}
+
+/// Ostium protocol event decoded from a transaction receipt
+#[derive(Debug, Clone)]
+pub enum OstiumEvent {
+    /// Trade open or close request on the Trading contract
+    Trade(TradeEvent),
+    /// USDC deposited into the OLP vault
+    Deposit {
+        sender: Address,
+        owner: Address,
+        /// USDC deposited (6 decimals)
+        assets: U256,
+        /// OLP shares minted (raw, OLP token decimals)
+        shares: U256,
+    },
+    /// USDC withdrawn from the OLP vault
+    Withdraw {
+        sender: Address,
+        receiver: Address,
+        owner: Address,
+        /// USDC withdrawn (6 decimals)
+        assets: U256,
+        /// OLP shares burned (raw, OLP token decimals)
+        shares: U256,
+    },
+}
+
+/// Concise, typed view of a transaction receipt
+#[derive(Debug, Clone)]
+pub struct ReceiptSummary {
+    /// Transaction hash
+    pub tx_hash: TxHash,
+    /// Whether the transaction succeeded
+    pub success: bool,
+    /// Block the transaction was included in
+    pub block_number: Option<u64>,
+    /// Gas used
+    pub gas_used: u64,
+    /// Effective gas price in wei
+    pub effective_gas_price: u128,
+    /// Ostium events emitted by the transaction
+    pub events: Vec<OstiumEvent>,
+}
+
+impl ReceiptSummary {
+    /// Total gas cost in wei (gas used * effective gas price)
+    pub fn total_cost(&self) -> U256 {
+        U256::from(self.gas_used) * U256::from(self.effective_gas_price)
+    }
+
+    /// Total gas cost in ETH
+    pub fn total_cost_eth(&self) -> f64 {
+        self.gas_used as f64 * self.effective_gas_price as f64 / 1e18
+    }
+}
+
+/// Human-readable view of Trading, OLP vault or ERC-20 calldata
+#[derive(Debug, Clone)]
+pub struct DecodedCall {
+    /// Contract interface the selector matched (`ITrading`, `IOstiumVault`, `IERC20`)
+    pub contract: String,
+    /// Function name, e.g. `openTrade`
+    pub function: String,
+    /// Arguments as `(name, value)` pairs; struct fields are flattened as
+    /// `t.collateral` and known amounts/prices are unscaled
+    pub args: Vec<(String, String)>,
+    /// Call wrapped by `delegatedAction`, if any
+    pub inner: Option<Box<DecodedCall>>,
+}
+
+impl DecodedCall {
+    /// Look up an argument value by its (flattened) name
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|(arg, _)| arg == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl std::fmt::Display for DecodedCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}(", self.contract, self.function)?;
+        for (i, (name, value)) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        write!(f, ")")?;
+        if let Some(inner) = &self.inner {
+            write!(f, " -> {}", inner)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! Connectivity check types for user-facing API
+
+use std::time::Duration;
+
+/// Result of probing one external dependency
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    /// Round-trip time of the probe (until failure, if it failed)
+    pub latency: Duration,
+    /// Error message if the probe failed
+    pub error: Option<String>,
+}
+
+impl EndpointStatus {
+    /// Whether the probe succeeded
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Reachability and latency of the RPC, price API, and subgraph
+#[derive(Debug, Clone)]
+pub struct ConnectivityReport {
+    /// RPC node (`eth_blockNumber`)
+    pub rpc: EndpointStatus,
+    /// Latest block number reported by the RPC
+    pub block_number: Option<u64>,
+    /// Ostium price API (latest prices)
+    pub price_api: EndpointStatus,
+    /// Subgraph (`_meta` query), `None` if no subgraph is configured
+    pub subgraph: Option<EndpointStatus>,
+}
+
+impl ConnectivityReport {
+    /// Whether every configured endpoint responded
+    pub fn is_healthy(&self) -> bool {
+        self.rpc.is_ok()
+            && self.price_api.is_ok()
+            && self.subgraph.as_ref().is_none_or(EndpointStatus::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_healthy() {
+        let ok = EndpointStatus {
+            latency: Duration::from_millis(20),
+            error: None,
+        };
+        let failed = EndpointStatus {
+            latency: Duration::from_secs(5),
+            error: Some("timeout".into()),
+        };
+        let mut report = ConnectivityReport {
+            rpc: ok.clone(),
+            block_number: Some(1),
+            price_api: ok.clone(),
+            subgraph: None,
+        };
+        assert!(report.is_healthy());
+
+        report.subgraph = Some(failed);
+        assert!(!report.is_healthy());
+
+        report.subgraph = Some(ok);
+        assert!(report.is_healthy());
+    }
+}
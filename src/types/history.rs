@@ -0,0 +1,299 @@
+//! Trade history types for user-facing API
+
+use super::pair::parse_scaled;
+use super::trade::Position;
+use crate::subgraph::SubgraphOrder;
+use alloy::primitives::TxHash;
+use eyre::{Context, Result};
+
+/// Kind of step in a trade's lifecycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleEventKind {
+    /// Trade opened
+    Open,
+    /// Part of the trade closed at market
+    PartialClose,
+    /// Remainder of the trade closed at market
+    Close,
+    /// Closed by its take profit
+    TakeProfit,
+    /// Closed by its stop loss
+    StopLoss,
+    /// Liquidated
+    Liquidation,
+    /// Take profit moved (the event's price is the new level, 0 if removed)
+    TakeProfitUpdated,
+    /// Stop loss moved (the event's price is the new level, 0 if removed)
+    StopLossUpdated,
+    /// Order cancelled before execution
+    Cancelled,
+    /// Any other order action reported by the subgraph
+    Other(String),
+}
+
+/// One step of a trade's history
+#[derive(Debug, Clone)]
+pub struct LifecycleEvent {
+    /// What happened
+    pub kind: LifecycleEventKind,
+    /// Unix timestamp of execution
+    pub timestamp: u64,
+    /// Execution price, or the new level for TP/SL updates
+    pub price: f64,
+    /// Collateral of the order in USDC (0 for TP/SL updates)
+    pub collateral: f64,
+    /// Leverage of the order (0 for TP/SL updates)
+    pub leverage: f64,
+    /// Percentage closed, for close events
+    pub close_percentage: Option<f64>,
+    /// Execution transaction
+    pub tx_hash: Option<TxHash>,
+}
+
+/// History of one trade, from the subgraph, with its current on-chain state
+#[derive(Debug, Clone)]
+pub struct TradeLifecycle {
+    /// Trading pair index
+    pub pair_index: u16,
+    /// Trade index
+    pub trade_index: u8,
+    /// Events of the latest trade in the slot, oldest first
+    pub events: Vec<LifecycleEvent>,
+    /// The position as currently stored on-chain, if still open
+    pub current: Option<Position>,
+}
+
+impl TradeLifecycle {
+    /// Assemble the timeline of the latest trade in a slot from its orders
+    ///
+    /// Orders before the latest `Open` belong to earlier trades that reused
+    /// the same slot and are dropped.
+    pub fn from_orders(
+        pair_index: u16,
+        trade_index: u8,
+        orders: &[SubgraphOrder],
+        current: Option<Position>,
+    ) -> Result<Self> {
+        let mut events = orders
+            .iter()
+            .map(LifecycleEvent::from_order)
+            .collect::<Result<Vec<_>>>()?;
+        events.sort_by_key(|event| event.timestamp);
+
+        if let Some(start) = events
+            .iter()
+            .rposition(|event| event.kind == LifecycleEventKind::Open)
+        {
+            events.drain(..start);
+        }
+
+        Ok(Self {
+            pair_index,
+            trade_index,
+            events,
+            current,
+        })
+    }
+
+    /// Merge TP/SL updates into the timeline
+    ///
+    /// Updates from before the trade's open belong to an earlier trade in the
+    /// slot and are dropped.
+    pub fn with_updates(mut self, updates: Vec<LifecycleEvent>) -> Self {
+        let opened_at = self.events.first().map_or(0, |event| event.timestamp);
+        self.events.extend(
+            updates
+                .into_iter()
+                .filter(|event| event.timestamp >= opened_at),
+        );
+        self.events.sort_by_key(|event| event.timestamp);
+        self
+    }
+
+    /// Whether the trade is still open on-chain
+    pub fn is_open(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Price the trade was opened at, if its open is in the history
+    pub fn open_price(&self) -> Option<f64> {
+        self.events
+            .iter()
+            .find(|event| event.kind == LifecycleEventKind::Open)
+            .map(|event| event.price)
+    }
+}
+
+impl LifecycleEvent {
+    /// Parse a subgraph order entity
+    pub fn from_order(order: &SubgraphOrder) -> Result<Self> {
+        let close_percentage = match &order.close_percent {
+            Some(value) => Some(parse_scaled(value, 2)?),
+            None => None,
+        };
+        let kind = if order.is_cancelled {
+            LifecycleEventKind::Cancelled
+        } else {
+            match order.order_action.as_str() {
+                "Open" => LifecycleEventKind::Open,
+                "Close" if close_percentage.is_some_and(|pct| pct < 100.0) => {
+                    LifecycleEventKind::PartialClose
+                }
+                "Close" => LifecycleEventKind::Close,
+                "TakeProfit" => LifecycleEventKind::TakeProfit,
+                "StopLoss" => LifecycleEventKind::StopLoss,
+                "Liquidation" => LifecycleEventKind::Liquidation,
+                other => LifecycleEventKind::Other(other.to_string()),
+            }
+        };
+        let tx_hash = match &order.executed_tx {
+            Some(tx) => Some(tx.parse().context("Invalid order transaction hash")?),
+            None => None,
+        };
+
+        Ok(Self {
+            kind,
+            timestamp: order
+                .executed_at
+                .parse()
+                .with_context(|| format!("Invalid order timestamp: {}", order.executed_at))?,
+            price: parse_scaled(&order.price, 18)?,
+            collateral: parse_scaled(&order.collateral, 6)?,
+            leverage: parse_scaled(&order.leverage, 2)?,
+            close_percentage,
+            tx_hash,
+        })
+    }
+}
+
+/// Realized PnL in USDC of executed close, TP/SL and liquidation orders
+///
+/// Each close realizes the USDC sent to the trader minus the collateral it
+/// closed (the order's collateral times its close percentage), so fees,
+/// funding and rollover are included. Open, cancelled and closes without a
+/// payout amount are skipped.
+pub fn realized_pnl(orders: &[SubgraphOrder]) -> Result<f64> {
+    let mut total = 0.0;
+    for order in orders {
+        if order.is_cancelled || order.order_action == "Open" {
+            continue;
+        }
+        let Some(amount_sent) = &order.amount_sent_to_trader else {
+            continue;
+        };
+        let close_fraction = match &order.close_percent {
+            Some(value) => parse_scaled(value, 2)? / 100.0,
+            None => 1.0,
+        };
+        let closed_collateral = parse_scaled(&order.collateral, 6)? * close_fraction;
+        total += parse_scaled(amount_sent, 6)? - closed_collateral;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(action: &str, executed_at: u64, close_percent: Option<&str>) -> SubgraphOrder {
+        SubgraphOrder {
+            id: executed_at.to_string(),
+            order_action: action.to_string(),
+            price: "65000000000000000000000".to_string(),
+            close_percent: close_percent.map(str::to_string),
+            collateral: "100000000".to_string(),
+            leverage: "1000".to_string(),
+            is_cancelled: false,
+            executed_at: executed_at.to_string(),
+            executed_tx: None,
+            amount_sent_to_trader: None,
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_from_orders() {
+        let orders = vec![
+            // An earlier trade that used the same slot
+            order("Open", 100, None),
+            order("Close", 200, Some("10000")),
+            // The current trade, out of order
+            order("Close", 400, Some("5000")),
+            order("Open", 300, None),
+            order("TakeProfit", 500, Some("10000")),
+        ];
+
+        let lifecycle = TradeLifecycle::from_orders(1, 0, &orders, None).unwrap();
+        let kinds: Vec<_> = lifecycle.events.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LifecycleEventKind::Open,
+                LifecycleEventKind::PartialClose,
+                LifecycleEventKind::TakeProfit,
+            ]
+        );
+        assert_eq!(lifecycle.events[0].timestamp, 300);
+        assert_eq!(lifecycle.events[1].close_percentage, Some(50.0));
+        assert_eq!(lifecycle.open_price(), Some(65_000.0));
+        assert_eq!(lifecycle.events[0].leverage, 10.0);
+        assert!(!lifecycle.is_open());
+    }
+
+    #[test]
+    fn test_lifecycle_with_updates() {
+        let orders = vec![
+            order("Open", 300, None),
+            order("Close", 600, Some("10000")),
+        ];
+        let update = |kind, timestamp, price| LifecycleEvent {
+            kind,
+            timestamp,
+            price,
+            collateral: 0.0,
+            leverage: 0.0,
+            close_percentage: None,
+            tx_hash: None,
+        };
+
+        let lifecycle = TradeLifecycle::from_orders(1, 0, &orders, None)
+            .unwrap()
+            .with_updates(vec![
+                update(LifecycleEventKind::StopLossUpdated, 450, 60_000.0),
+                update(LifecycleEventKind::TakeProfitUpdated, 200, 70_000.0),
+            ]);
+        let kinds: Vec<_> = lifecycle.events.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LifecycleEventKind::Open,
+                LifecycleEventKind::StopLossUpdated,
+                LifecycleEventKind::Close,
+            ]
+        );
+        assert_eq!(lifecycle.events[1].price, 60_000.0);
+    }
+
+    #[test]
+    fn test_realized_pnl() {
+        let paid = |mut order: SubgraphOrder, amount: &str| {
+            order.amount_sent_to_trader = Some(amount.to_string());
+            order
+        };
+        let mut cancelled = paid(order("Close", 500, None), "999000000");
+        cancelled.is_cancelled = true;
+
+        let orders = vec![
+            // Half of 100 USDC closed for 54.2: +4.2
+            paid(order("Close", 100, Some("5000")), "54200000"),
+            // Take profit on the full 100 USDC: +20
+            paid(order("TakeProfit", 200, Some("10000")), "120000000"),
+            // Liquidated: nothing paid back, -100
+            paid(order("Liquidation", 300, None), "0"),
+            paid(order("Open", 400, None), "0"),
+            cancelled,
+            // No payout reported
+            order("Close", 600, None),
+        ];
+        assert!((realized_pnl(&orders).unwrap() - -75.8).abs() < 1e-9);
+    }
+}
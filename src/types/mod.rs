@@ -1,7 +1,20 @@
 //! Type definitions for Ostium SDK
 
+mod account;
+mod event;
+mod health;
+mod history;
+mod pair;
+mod token;
 pub mod trade;
 mod vault;
 
+pub use account::*;
+pub use event::*;
+pub use health::*;
+pub use history::*;
+pub(crate) use pair::parse_scaled;
+pub use pair::*;
+pub use token::*;
 pub use trade::*;
 pub use vault::*;
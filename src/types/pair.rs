@@ -0,0 +1,156 @@
+//! Trading pair types for user-facing API
+
+use crate::price::PriceData;
+use crate::subgraph::SubgraphPair;
+use eyre::{Context, Result};
+
+/// Snapshot of a trading pair's configuration and current market
+#[derive(Debug, Clone)]
+pub struct PairInfo {
+    /// Trading pair index
+    pub pair_index: u16,
+    /// Base asset symbol (e.g. "BTC")
+    pub from: String,
+    /// Quote asset symbol (e.g. "USD")
+    pub to: String,
+    /// Asset group name (e.g. "crypto", "forex")
+    pub group: String,
+    /// Current bid price
+    pub bid: f64,
+    /// Current ask price
+    pub ask: f64,
+    /// Current mid price
+    pub mid: f64,
+    /// Bid/ask spread as a percentage of mid
+    pub spread_percent: f64,
+    /// Whether the market is currently open
+    pub is_market_open: bool,
+    /// Minimum leverage
+    pub min_leverage: f64,
+    /// Maximum leverage
+    pub max_leverage: f64,
+    /// Maker fee in percent of notional
+    pub maker_fee_percent: f64,
+    /// Taker (market order) fee in percent of notional
+    pub taker_fee_percent: f64,
+    /// Minimum position size (collateral * leverage) in USDC
+    pub min_position_size: f64,
+    /// Current long open interest in units of the base asset
+    pub long_oi: f64,
+    /// Current short open interest in units of the base asset
+    pub short_oi: f64,
+    /// Max open interest per side in USDC
+    pub max_oi: f64,
+}
+
+impl PairInfo {
+    /// Build from the subgraph pair entity and a price feed quote
+    pub fn from_parts(pair: &SubgraphPair, price: &PriceData) -> Result<Self> {
+        let group_max_leverage = parse_scaled(&pair.group.max_leverage, 2)?;
+        // A pair-level max leverage of 0 means the group limit applies
+        let pair_max_leverage = match &pair.max_leverage {
+            Some(value) => parse_scaled(value, 2)?,
+            None => 0.0,
+        };
+        let max_leverage = if pair_max_leverage > 0.0 {
+            pair_max_leverage
+        } else {
+            group_max_leverage
+        };
+
+        Ok(Self {
+            pair_index: pair.id.parse().context("Invalid pair index")?,
+            from: pair.from.clone(),
+            to: pair.to.clone(),
+            group: pair.group.name.clone(),
+            bid: price.bid,
+            ask: price.ask,
+            mid: price.mid,
+            spread_percent: price.spread_percent(),
+            is_market_open: price.is_market_open,
+            min_leverage: parse_scaled(&pair.group.min_leverage, 2)?,
+            max_leverage,
+            maker_fee_percent: parse_scaled(&pair.maker_fee_p, 6)?,
+            taker_fee_percent: parse_scaled(&pair.taker_fee_p, 6)?,
+            min_position_size: parse_scaled(&pair.fee.min_lev_pos, 6)?,
+            long_oi: parse_scaled(&pair.long_oi, 18)?,
+            short_oi: parse_scaled(&pair.short_oi, 18)?,
+            max_oi: parse_scaled(&pair.max_oi, 6)?,
+        })
+    }
+
+    /// Open interest on one side in USDC, valued at the mid price
+    pub fn oi_notional(&self, is_long: bool) -> f64 {
+        let oi = if is_long { self.long_oi } else { self.short_oi };
+        oi * self.mid
+    }
+
+    /// Open interest still available on one side in USDC (`None` if uncapped)
+    pub fn available_oi(&self, is_long: bool) -> Option<f64> {
+        (self.max_oi > 0.0).then(|| (self.max_oi - self.oi_notional(is_long)).max(0.0))
+    }
+
+    /// Minimum collateral needed to open a position at the given leverage
+    pub fn min_collateral(&self, leverage: f64) -> f64 {
+        if leverage <= 0.0 {
+            return self.min_position_size;
+        }
+        self.min_position_size / leverage
+    }
+}
+
+/// Parse a scaled integer string from the subgraph into a float
+pub(crate) fn parse_scaled(value: &str, decimals: i32) -> Result<f64> {
+    let raw: f64 = value
+        .parse()
+        .with_context(|| format!("Invalid numeric value: {}", value))?;
+    Ok(raw / 10f64.powi(decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_info_from_parts() {
+        let pair: SubgraphPair = serde_json::from_value(serde_json::json!({
+            "id": "0",
+            "from": "BTC",
+            "to": "USD",
+            "longOI": "1500000000000000000",
+            "shortOI": "500000000000000000",
+            "maxOI": "10000000000",
+            "makerFeeP": "30000",
+            "takerFeeP": "50000",
+            "maxLeverage": "0",
+            "group": { "name": "crypto", "minLeverage": "200", "maxLeverage": "10000" },
+            "fee": { "minLevPos": "1500000000" }
+        }))
+        .unwrap();
+        let price = PriceData {
+            from: "BTC".to_string(),
+            to: "USD".to_string(),
+            bid: 49_990.0,
+            mid: 50_000.0,
+            ask: 50_010.0,
+            is_market_open: true,
+            is_day_trading_closed: false,
+        };
+
+        let info = PairInfo::from_parts(&pair, &price).unwrap();
+        assert_eq!(info.pair_index, 0);
+        assert_eq!(info.min_leverage, 2.0);
+        // Pair override of 0 falls back to the group max
+        assert_eq!(info.max_leverage, 100.0);
+        assert!((info.taker_fee_percent - 0.05).abs() < 1e-12);
+        assert!((info.spread_percent - 0.04).abs() < 1e-9);
+        assert_eq!(info.min_position_size, 1_500.0);
+        assert_eq!(info.min_collateral(10.0), 150.0);
+        assert_eq!(info.long_oi, 1.5);
+        // 1.5 BTC long at $50,000 is $75,000 of notional
+        assert_eq!(info.oi_notional(true), 75_000.0);
+        assert_eq!(info.oi_notional(false), 25_000.0);
+        // The $10,000 cap is already exceeded on the long side
+        assert_eq!(info.available_oi(true), Some(0.0));
+    }
+}
@@ -0,0 +1,22 @@
+//! Token metadata types for user-facing API
+
+/// ERC-20 token metadata read from the token contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    /// Token name (e.g. "USD Coin")
+    pub name: String,
+    /// Token symbol (e.g. "USDC")
+    pub symbol: String,
+    /// Token decimals (6 for USDC)
+    pub decimals: u8,
+}
+
+/// Allowance to grant when an approval is needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApprovalMode {
+    /// Approve exactly the amount being spent
+    #[default]
+    Exact,
+    /// Approve an unlimited amount, so later spends need no approval
+    Max,
+}
@@ -1,12 +1,20 @@
 //! Trading types for user-facing API
 
+use super::pair::PairInfo;
 use crate::constants::{
-    scale_leverage, scale_price, scale_slippage, scale_usdc, DEFAULT_SLIPPAGE, MAX_LEVERAGE,
-    MAX_SLIPPAGE, MIN_LEVERAGE,
+    checked_scale_leverage, scale_leverage, scale_price, scale_usdc, slippage_basis_points,
+    DEFAULT_SLIPPAGE, DEPTH_IMPACT_SLIPPAGE, LIQUIDATION_THRESHOLD_PERCENT, MAX_LEVERAGE,
+    MAX_SLIPPAGE, MIN_LEVERAGE, RECOMMENDED_SLIPPAGE_BUFFER,
 };
-use crate::contracts::{BuilderFee, OrderType, Trade};
-use alloy::primitives::{Address, Uint, U256};
+use crate::contracts::{BuilderFee, ITrading, OrderType, Trade};
+use crate::error::OstiumError;
+use crate::price::PriceData;
+use alloy::primitives::{Address, Bytes, TxHash, Uint, U256};
+use alloy::sol_types::SolCall;
 use eyre::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
 
 /// Type alias for U192 (used for prices in Ostium)
 pub type U192 = Uint<192, 3>;
@@ -18,8 +26,89 @@ pub fn u256_to_u192(value: U256) -> U192 {
     U192::from_limbs([limbs[0], limbs[1], limbs[2]])
 }
 
+/// How an order's slippage tolerance is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlippageMode {
+    /// Use the order's `slippage` percentage as-is
+    #[default]
+    Fixed,
+    /// Size slippage from the pair's spread and short-term volatility at
+    /// submission time, clamped to `floor..=ceiling` (percent)
+    Auto { floor: f64, ceiling: f64 },
+}
+
+impl SlippageMode {
+    /// Auto slippage percentage for a window of price samples (oldest first)
+    ///
+    /// Uses the latest bid/ask spread plus twice the mid-price range over the
+    /// window, clamped to the mode's bounds. Returns `None` for `Fixed` or
+    /// when there are no samples.
+    pub fn resolve(&self, samples: &[PriceData]) -> Option<f64> {
+        let SlippageMode::Auto { floor, ceiling } = *self else {
+            return None;
+        };
+        let latest = samples.last()?;
+
+        let mids = samples.iter().map(|s| s.mid);
+        let high = mids.clone().fold(f64::MIN, f64::max);
+        let low = mids.fold(f64::MAX, f64::min);
+        let range_percent = if latest.mid > 0.0 {
+            (high - low) / latest.mid * 100.0
+        } else {
+            0.0
+        };
+
+        Some((latest.spread_percent() + 2.0 * range_percent).clamp(floor, ceiling))
+    }
+}
+
+/// Slippage suggested for an order, with the inputs it was derived from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlippageRecommendation {
+    /// Suggested slippage tolerance in percent
+    pub slippage_percent: f64,
+    /// Pair's current bid/ask spread in percent of mid
+    pub spread_percent: f64,
+    /// Order notional (collateral * leverage) in USDC
+    pub notional: f64,
+    /// Open interest still available on the order's side in USDC (unset if uncapped)
+    pub available_oi: Option<f64>,
+    /// Share of the available open interest the order takes (0 if uncapped)
+    pub depth_ratio: f64,
+}
+
+impl SlippageRecommendation {
+    /// Recommend slippage from the spread and the order's size against available OI
+    ///
+    /// The spread is paid on entry, [`RECOMMENDED_SLIPPAGE_BUFFER`] covers price
+    /// moves before execution, and up to [`DEPTH_IMPACT_SLIPPAGE`] is added in
+    /// proportion to how much of the remaining open interest the order takes.
+    pub fn new(spread_percent: f64, notional: f64, available_oi: Option<f64>) -> Self {
+        let depth_ratio = match available_oi {
+            Some(available) if available > 0.0 => (notional / available).min(1.0),
+            Some(_) => 1.0,
+            None => 0.0,
+        };
+        let slippage_percent =
+            (spread_percent + RECOMMENDED_SLIPPAGE_BUFFER + depth_ratio * DEPTH_IMPACT_SLIPPAGE)
+                .min(MAX_SLIPPAGE);
+        Self {
+            slippage_percent,
+            spread_percent,
+            notional,
+            available_oi,
+            depth_ratio,
+        }
+    }
+}
+
 /// Parameters for placing a new order
-#[derive(Debug, Clone)]
+///
+/// Serializable so order specs can be stored and replayed later; missing
+/// fields take their [`Default`] values when deserializing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PlaceOrderParams {
     /// Trading pair index (e.g., 0 = BTC/USD)
     pub pair_index: u16,
@@ -37,10 +126,21 @@ pub struct PlaceOrderParams {
     pub take_profit: Option<f64>,
     /// Stop loss price (optional)
     pub stop_loss: Option<f64>,
-    /// Slippage tolerance in percentage (default: 2%)
+    /// Slippage tolerance in percentage (unset uses the client's default
+    /// slippage, else 2%)
     pub slippage: Option<f64>,
+    /// How `slippage` is chosen (fixed by default)
+    pub slippage_mode: SlippageMode,
     /// Trade index (0-2, auto-selected if None)
     pub trade_index: Option<u8>,
+    /// Pair as `(from, to)` symbols, resolved to `pair_index` by the client
+    /// when the order is placed (see [`Self::market_symbol`])
+    pub pair_symbol: Option<(String, String)>,
+    /// Trader the trade is opened for (defaults to the signer)
+    ///
+    /// When set to another address, the signer must be that trader's approved
+    /// delegate, and the collateral is pulled from the trader.
+    pub trader: Option<Address>,
 }
 
 impl Default for PlaceOrderParams {
@@ -54,8 +154,11 @@ impl Default for PlaceOrderParams {
             open_price: None,
             take_profit: None,
             stop_loss: None,
-            slippage: Some(DEFAULT_SLIPPAGE),
+            slippage: None,
+            slippage_mode: SlippageMode::Fixed,
             trade_index: None,
+            pair_symbol: None,
+            trader: None,
         }
     }
 }
@@ -73,12 +176,88 @@ impl PlaceOrderParams {
         }
     }
 
+    /// Create a new market order for a pair given by symbol (e.g. "BTC", "USD")
+    ///
+    /// `OstiumClient::place_order` resolves the symbols to the pair's current
+    /// index through [`OstiumClient::resolve_pair`](crate::OstiumClient::resolve_pair).
+    pub fn market_symbol(
+        from: &str,
+        to: &str,
+        collateral: f64,
+        leverage: f64,
+        is_long: bool,
+    ) -> Self {
+        Self {
+            pair_symbol: Some((from.to_string(), to.to_string())),
+            ..Self::market(0, collateral, leverage, is_long)
+        }
+    }
+
+    /// Create a new long market order
+    pub fn long(pair_index: u16, collateral: f64, leverage: f64) -> Self {
+        Self::market(pair_index, collateral, leverage, true)
+    }
+
+    /// Create a new short market order
+    pub fn short(pair_index: u16, collateral: f64, leverage: f64) -> Self {
+        Self::market(pair_index, collateral, leverage, false)
+    }
+
     /// Set slippage tolerance
     pub fn with_slippage(mut self, slippage_percent: f64) -> Self {
         self.slippage = Some(slippage_percent);
         self
     }
 
+    /// Open the trade on behalf of another trader via delegation
+    pub fn with_trader(mut self, trader: Address) -> Self {
+        self.trader = Some(trader);
+        self
+    }
+
+    /// Size slippage automatically at submission, within `floor..=ceiling` percent
+    ///
+    /// `OstiumClient::place_order` samples the price feed for
+    /// [`AUTO_SLIPPAGE_SAMPLES`](crate::constants::AUTO_SLIPPAGE_SAMPLES) prices
+    /// [`AUTO_SLIPPAGE_SAMPLE_INTERVAL_MS`](crate::constants::AUTO_SLIPPAGE_SAMPLE_INTERVAL_MS)
+    /// apart (about 2 seconds) and resolves the tolerance from them. Until
+    /// resolved, the ceiling is used.
+    pub fn with_auto_slippage(mut self, floor: f64, ceiling: f64) -> Self {
+        self.slippage_mode = SlippageMode::Auto { floor, ceiling };
+        self.slippage = None;
+        self
+    }
+
+    /// Slippage percentage that will be encoded
+    ///
+    /// Auto mode uses the resolved value, or the ceiling if not resolved yet.
+    pub fn effective_slippage(&self) -> f64 {
+        match self.slippage_mode {
+            SlippageMode::Fixed => self.slippage.unwrap_or(DEFAULT_SLIPPAGE),
+            SlippageMode::Auto { ceiling, .. } => self.slippage.unwrap_or(ceiling),
+        }
+    }
+
+    /// Worst acceptable fill for opening at `price` with this order's slippage
+    ///
+    /// A long opens by buying, so it tolerates prices above `price`; a short
+    /// opens by selling, so it tolerates prices below it.
+    pub fn worst_case_price(&self, price: f64) -> f64 {
+        let slippage = self.effective_slippage() / 100.0;
+        if self.is_long {
+            price * (1.0 + slippage)
+        } else {
+            price * (1.0 - slippage)
+        }
+    }
+
+    /// Liquidation price if the order fills at `price`
+    ///
+    /// See [`Position::liquidation_price`].
+    pub fn liquidation_price(&self, price: f64) -> f64 {
+        liquidation_price(price, self.leverage, self.is_long)
+    }
+
     /// Set open price (required for market orders to set expected price)
     pub fn with_open_price(mut self, price: f64) -> Self {
         self.open_price = Some(price);
@@ -87,7 +266,15 @@ impl PlaceOrderParams {
 
     /// Validate parameters
     pub fn validate(&self) -> Result<()> {
+        if let Some((from, to)) = &self.pair_symbol {
+            eyre::bail!(
+                "Pair {}/{} is not resolved to an index; use OstiumClient::resolve_pair",
+                from,
+                to
+            );
+        }
         ensure!(self.collateral > 0.0, "Collateral must be positive");
+        checked_scale_leverage(self.leverage)?;
         ensure!(
             self.leverage >= MIN_LEVERAGE && self.leverage <= MAX_LEVERAGE,
             "Leverage must be between {} and {}",
@@ -97,12 +284,20 @@ impl PlaceOrderParams {
 
         if let Some(slippage) = self.slippage {
             ensure!(
-                slippage >= 0.0 && slippage <= MAX_SLIPPAGE,
+                (0.0..=MAX_SLIPPAGE).contains(&slippage),
                 "Slippage must be between 0 and {}%",
                 MAX_SLIPPAGE
             );
         }
 
+        if let SlippageMode::Auto { floor, ceiling } = self.slippage_mode {
+            ensure!(
+                0.0 <= floor && floor <= ceiling && ceiling <= MAX_SLIPPAGE,
+                "Auto slippage bounds must satisfy 0 <= floor <= ceiling <= {}%",
+                MAX_SLIPPAGE
+            );
+        }
+
         if self.order_type != OrderType::Market {
             ensure!(
                 self.open_price.is_some(),
@@ -113,13 +308,26 @@ impl PlaceOrderParams {
         Ok(())
     }
 
+    /// Check the collateral meets a pair's minimum at this order's leverage
+    pub fn check_min_collateral(&self, min_collateral: f64) -> Result<()> {
+        ensure!(
+            self.collateral >= min_collateral,
+            "Collateral {} is below the minimum of {} USDC for pair {} at {}x",
+            self.collateral,
+            min_collateral,
+            self.pair_index,
+            self.leverage
+        );
+        Ok(())
+    }
+
     /// Convert to contract Trade struct
     pub fn to_trade(&self, trader: Address, trade_index: u8) -> Trade {
-        let collateral = scale_usdc(self.collateral);
+        let collateral = self.scaled_collateral();
         let open_price = u256_to_u192(self.open_price.map(scale_price).unwrap_or(U256::ZERO));
         let tp = u256_to_u192(self.take_profit.map(scale_price).unwrap_or(U256::ZERO));
         let sl = u256_to_u192(self.stop_loss.map(scale_price).unwrap_or(U256::ZERO));
-        let leverage = scale_leverage(self.leverage);
+        let leverage = self.scaled_leverage();
 
         Trade {
             collateral,
@@ -134,17 +342,43 @@ impl PlaceOrderParams {
         }
     }
 
+    /// Encode `openTrade` calldata for the Trading contract
+    ///
+    /// Useful for batching the order into a smart-wallet multicall or submitting
+    /// it through an external relayer instead of the SDK's signer.
+    pub fn encode_calldata(
+        &self,
+        trader: Address,
+        trade_index: u8,
+        builder_fee: Option<BuilderFeeParams>,
+    ) -> Bytes {
+        let call = ITrading::openTradeCall {
+            t: self.to_trade(trader, trade_index),
+            bf: builder_fee.unwrap_or_default().to_builder_fee(),
+            orderType: self.order_type.into(),
+            slippageP: self.scaled_slippage(),
+        };
+        Bytes::from(call.abi_encode())
+    }
+
+    /// Get collateral scaled to USDC decimals (6)
+    pub fn scaled_collateral(&self) -> U256 {
+        scale_usdc(self.collateral)
+    }
+
+    /// Get leverage scaled (PRECISION_2 = 100, so 10x = 1000)
+    pub fn scaled_leverage(&self) -> u32 {
+        scale_leverage(self.leverage)
+    }
+
     /// Get slippage as scaled value (PRECISION_2 = 100)
     pub fn scaled_slippage(&self) -> U256 {
-        let slippage = self.slippage.unwrap_or(DEFAULT_SLIPPAGE);
-        // Slippage uses PRECISION_2 (100), so 2% = 200
-        let scaled = (slippage * 100.0) as u128;
-        U256::from(scaled)
+        U256::from(slippage_basis_points(self.effective_slippage()))
     }
 }
 
 /// Parameters for closing a trade
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloseTradeParams {
     /// Trading pair index
     pub pair_index: u16,
@@ -154,7 +388,8 @@ pub struct CloseTradeParams {
     pub close_percentage: f64,
     /// Current market price estimate
     pub market_price: f64,
-    /// Slippage tolerance in percentage
+    /// Slippage tolerance in percentage (unset uses the client's default
+    /// slippage, else 2%)
     pub slippage: Option<f64>,
 }
 
@@ -166,13 +401,86 @@ impl CloseTradeParams {
             trade_index,
             close_percentage: 100.0,
             market_price,
-            slippage: Some(DEFAULT_SLIPPAGE),
+            slippage: None,
+        }
+    }
+
+    /// Set slippage tolerance
+    pub fn with_slippage(mut self, slippage_percent: f64) -> Self {
+        self.slippage = Some(slippage_percent);
+        self
+    }
+
+    /// Set the slippage from the worst price acceptable for the close
+    ///
+    /// Back-computes the percentage slippage between `market_price` and
+    /// `limit_price`. A long closes by selling, so its limit must be at or
+    /// below the market; a short closes by buying, so its limit must be at or
+    /// above it. The slippage is floored to whole basis points, since the
+    /// encoder rounds to them, so execution never goes past the limit. For
+    /// example, closing a long at $66,000 with a $65,000 limit (~1.5152%)
+    /// sets slippage to 1.51%.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit_price` - Worst acceptable execution price
+    /// * `is_long` - Direction of the position being closed
+    pub fn with_limit_price(mut self, limit_price: f64, is_long: bool) -> Result<Self> {
+        ensure!(
+            self.market_price.is_finite() && self.market_price > 0.0,
+            "Market price must be positive"
+        );
+        ensure!(
+            limit_price.is_finite() && limit_price > 0.0,
+            "Limit price must be positive"
+        );
+        if is_long {
+            ensure!(
+                limit_price <= self.market_price,
+                "Limit price {} is above the market price {} for a long close",
+                limit_price,
+                self.market_price
+            );
+        } else {
+            ensure!(
+                limit_price >= self.market_price,
+                "Limit price {} is below the market price {} for a short close",
+                limit_price,
+                self.market_price
+            );
         }
+
+        let slippage = (limit_price - self.market_price).abs() / self.market_price * 100.0;
+        ensure!(
+            slippage <= MAX_SLIPPAGE,
+            "Limit price {} implies {:.2}% slippage, above the {}% maximum",
+            limit_price,
+            slippage,
+            MAX_SLIPPAGE
+        );
+        self.slippage = Some((slippage * 100.0).floor() / 100.0);
+        Ok(self)
+    }
+
+    /// Validate close parameters before encoding
+    ///
+    /// The close percentage must be finite and within (0, 100].
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.close_percentage.is_finite()
+                && self.close_percentage > 0.0
+                && self.close_percentage <= 100.0,
+            "Close percentage {} must be above 0 and at most 100",
+            self.close_percentage
+        );
+        Ok(())
     }
 
     /// Get close percentage scaled (10000 = 100%)
+    ///
+    /// Rounds to the nearest basis point, like [`scale_leverage`].
     pub fn scaled_close_percentage(&self) -> u16 {
-        (self.close_percentage * 100.0) as u16
+        (self.close_percentage * 100.0).round() as u16
     }
 
     /// Get market price scaled as U192
@@ -181,14 +489,258 @@ impl CloseTradeParams {
     }
 
     /// Get slippage scaled (100 = 1%)
+    ///
+    /// The contract applies `slippageP` around `marketPrice` using the stored
+    /// trade's direction, so the same encoded value is correct for longs and shorts.
     pub fn scaled_slippage(&self) -> u32 {
-        let slippage = self.slippage.unwrap_or(DEFAULT_SLIPPAGE);
-        scale_slippage(slippage) as u32
+        slippage_basis_points(self.slippage.unwrap_or(DEFAULT_SLIPPAGE))
+    }
+
+    /// Encode `closeTradeMarket` calldata for the Trading contract
+    pub fn encode_calldata(&self) -> Bytes {
+        let call = ITrading::closeTradeMarketCall {
+            pairIndex: self.pair_index,
+            index: self.trade_index,
+            closePercentage: self.scaled_close_percentage(),
+            marketPrice: self.scaled_market_price(),
+            slippageP: self.scaled_slippage(),
+        };
+        Bytes::from(call.abi_encode())
+    }
+
+    /// Worst acceptable execution price for closing a position in the given direction
+    ///
+    /// A long closes by selling, so it tolerates prices below `market_price`;
+    /// a short closes by buying, so it tolerates prices above it.
+    pub fn worst_case_price(&self, is_long: bool) -> f64 {
+        let slippage = self.slippage.unwrap_or(DEFAULT_SLIPPAGE) / 100.0;
+        if is_long {
+            self.market_price * (1.0 - slippage)
+        } else {
+            self.market_price * (1.0 + slippage)
+        }
+    }
+}
+
+/// Estimated protocol fees for a trade (all values in USDC)
+#[derive(Debug, Clone)]
+pub struct TradeFees {
+    /// Fee rate applied to notional, in percent
+    pub fee_percent: f64,
+    /// Fee charged when opening
+    pub open_fee: f64,
+    /// Estimated fee charged when closing (at the same notional)
+    pub close_fee: f64,
+    /// Collateral remaining after the open fee is deducted
+    pub effective_collateral: f64,
+}
+
+impl TradeFees {
+    /// Compute fees for the given collateral and leverage at a fee rate (percent of notional)
+    pub fn new(collateral: f64, leverage: f64, fee_percent: f64) -> Self {
+        let notional = collateral * leverage;
+        let open_fee = notional * fee_percent / 100.0;
+        Self {
+            fee_percent,
+            open_fee,
+            close_fee: open_fee,
+            effective_collateral: collateral - open_fee,
+        }
+    }
+
+    /// Total round-trip fees (open + close)
+    pub fn total(&self) -> f64 {
+        self.open_fee + self.close_fee
+    }
+}
+
+/// Everything a "confirm order" screen shows before an order is placed
+#[derive(Debug, Clone)]
+pub struct TradeTicket {
+    /// Pair info at the live price, including the leverage limits
+    pub pair: PairInfo,
+    /// Expected fill: the order's open price, or the live ask (long) / bid (short)
+    pub entry_price: f64,
+    /// Worst fill the order's slippage accepts
+    pub worst_case_price: f64,
+    /// Estimated open and close fees
+    pub fees: TradeFees,
+    /// Collateral the order locks, in USDC
+    pub collateral: f64,
+    /// Pair's minimum collateral at the order's leverage, in USDC
+    pub min_collateral: f64,
+    /// Liquidation price if filled at the entry price
+    pub liquidation_price: f64,
+    /// Trader's USDC balance
+    pub usdc_balance: f64,
+    /// Whether the trader's USDC allowance to TradingStorage covers the collateral
+    pub allowance_sufficient: bool,
+    /// Signer's ETH balance
+    pub eth_balance: f64,
+    /// Estimated gas cost in ETH (unset if the allowance is missing or estimation failed)
+    pub gas_cost_eth: Option<f64>,
+    /// Why the order would be rejected before submission, if it would be
+    pub validation_error: Option<String>,
+}
+
+impl TradeTicket {
+    /// Whether the order can be placed as is: valid, market open, funded and
+    /// approved, with enough ETH for gas
+    pub fn is_ready(&self) -> bool {
+        let gas_covered = match self.gas_cost_eth {
+            Some(cost) => cost <= self.eth_balance,
+            None => false,
+        };
+        self.validation_error.is_none()
+            && self.pair.is_market_open
+            && self.usdc_balance >= self.collateral
+            && self.allowance_sufficient
+            && gas_covered
+    }
+}
+
+/// Realized outcome of a confirmed market close, values in USDC
+#[derive(Debug, Clone)]
+pub struct CloseResult {
+    /// Hash of the close request
+    pub request_tx: TxHash,
+    /// Hash of the oracle callback that paid out, if a payout was found
+    pub execution_tx: Option<TxHash>,
+    /// Collateral released by the close
+    pub closed_collateral: f64,
+    /// USDC paid back to the trader
+    pub proceeds: f64,
+    /// Proceeds minus the closed collateral, after all fees
+    pub realized_pnl: f64,
+}
+
+impl CloseResult {
+    /// Build a result from the closed collateral and the USDC paid out
+    pub fn new(
+        request_tx: TxHash,
+        execution_tx: Option<TxHash>,
+        closed_collateral: f64,
+        proceeds: f64,
+    ) -> Self {
+        Self {
+            request_tx,
+            execution_tx,
+            closed_collateral,
+            proceeds,
+            realized_pnl: proceeds - closed_collateral,
+        }
+    }
+}
+
+/// Expected outcome of closing (part of) a position, all values in USDC
+#[derive(Debug, Clone)]
+pub struct CloseQuote {
+    /// Price the close is quoted at
+    pub close_price: f64,
+    /// Collateral released by the close
+    pub closed_collateral: f64,
+    /// PnL on the closed portion before fees
+    pub gross_pnl: f64,
+    /// Closing fee on the closed notional
+    pub close_fee: f64,
+    /// PnL after the closing fee
+    pub net_pnl: f64,
+    /// USDC expected back (collateral + net PnL, never below zero)
+    pub proceeds: f64,
+}
+
+impl CloseQuote {
+    /// Quote closing `close_percentage` percent of a position at a price and fee rate
+    ///
+    /// Excludes funding and rollover fees accrued while the trade was open.
+    pub fn new(
+        position: &Position,
+        close_percentage: f64,
+        close_price: f64,
+        fee_percent: f64,
+    ) -> Self {
+        let fraction = close_percentage / 100.0;
+        let closed_collateral = position.collateral * fraction;
+        let gross_pnl = position.pnl_at(close_price) * fraction;
+        let close_fee = position.notional() * fraction * fee_percent / 100.0;
+        let net_pnl = gross_pnl - close_fee;
+
+        Self {
+            close_price,
+            closed_collateral,
+            gross_pnl,
+            close_fee,
+            net_pnl,
+            proceeds: (closed_collateral + net_pnl).max(0.0),
+        }
+    }
+}
+
+/// Effect of adding or removing collateral on a position, all values in USDC
+///
+/// The position size (notional) stays fixed, so adding collateral lowers the
+/// effective leverage and moves the liquidation price away, and removing it
+/// does the opposite.
+#[derive(Debug, Clone)]
+pub struct CollateralPreview {
+    /// Collateral change that was previewed (negative for a removal)
+    pub delta: f64,
+    /// Collateral after the change
+    pub collateral: f64,
+    /// Effective leverage after the change
+    pub leverage: f64,
+    /// Liquidation price before the change
+    pub current_liquidation_price: f64,
+    /// Liquidation price after the change
+    pub liquidation_price: f64,
+}
+
+impl CollateralPreview {
+    /// Preview changing a position's collateral by `delta` USDC
+    ///
+    /// Fails if the change would leave no collateral or push leverage outside
+    /// the protocol's limits.
+    pub fn new(position: &Position, delta: f64) -> Result<Self> {
+        ensure!(
+            delta.is_finite(),
+            "Collateral change {} is not finite",
+            delta
+        );
+        let collateral = position.collateral + delta;
+        ensure!(
+            collateral > 0.0,
+            "Removing {} USDC leaves no collateral (position has {})",
+            -delta,
+            position.collateral
+        );
+
+        let leverage = position.notional() / collateral;
+        ensure!(
+            (MIN_LEVERAGE..=MAX_LEVERAGE).contains(&leverage),
+            "Resulting leverage {:.2}x outside {}..={}",
+            leverage,
+            MIN_LEVERAGE,
+            MAX_LEVERAGE
+        );
+
+        let adjusted = Position {
+            collateral,
+            leverage,
+            ..position.clone()
+        };
+
+        Ok(Self {
+            delta,
+            collateral,
+            leverage,
+            current_liquidation_price: position.liquidation_price(),
+            liquidation_price: adjusted.liquidation_price(),
+        })
     }
 }
 
 /// Builder fee parameters (for referral/builder rewards)
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BuilderFeeParams {
     /// Builder address
     pub builder: Option<Address>,
@@ -211,8 +763,68 @@ impl BuilderFeeParams {
     }
 }
 
+/// Protocol-wide index limits read from TradingStorage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolLimits {
+    /// Number of listed trading pairs
+    pub pairs_count: u16,
+    /// Maximum open trades (and pending orders) per trader per pair
+    pub max_trades_per_pair: u8,
+}
+
+impl ProtocolLimits {
+    /// Check that a pair/trade index pair is within the limits
+    pub fn check_index(&self, pair_index: u16, trade_index: u8) -> Result<()> {
+        if pair_index >= self.pairs_count {
+            return Err(OstiumError::InvalidIndex(format!(
+                "Pair index {} out of range (protocol has {} pairs)",
+                pair_index, self.pairs_count
+            ))
+            .into());
+        }
+        if trade_index >= self.max_trades_per_pair {
+            return Err(OstiumError::InvalidIndex(format!(
+                "Trade index {} out of range (max {} trades per pair)",
+                trade_index, self.max_trades_per_pair
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Trade slots left on a pair with `open_trades` already open
+    pub fn available_slots(&self, open_trades: u32) -> u8 {
+        let open = u8::try_from(open_trades).unwrap_or(u8::MAX);
+        self.max_trades_per_pair.saturating_sub(open)
+    }
+}
+
+/// A pending limit or stop order awaiting its trigger price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenOrder {
+    /// Trader address
+    pub trader: Address,
+    /// Trading pair index
+    pub pair_index: u16,
+    /// Order index within the pair
+    pub index: u8,
+}
+
+/// Where a [`Position`] was read from
+///
+/// Every loader currently reads the TradingStorage contract; a subgraph
+/// variant will be added alongside a subgraph position loader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum PositionSource {
+    /// TradingStorage contract (authoritative, current block)
+    #[default]
+    Contract,
+}
+
 /// Position information returned from queries
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Position {
     /// Trader address
     pub trader: Address,
@@ -234,4 +846,893 @@ pub struct Position {
     pub stop_loss: Option<f64>,
     /// Unrealized PnL (if available)
     pub unrealized_pnl: Option<f64>,
+    /// Protocol trade ID
+    pub trade_id: U256,
+    /// Unix timestamp of the last open/update of the trade
+    pub opened_at: u64,
+    /// Block number of the last open/update of the trade
+    pub opened_block: u64,
+    /// Data source the position was read from
+    pub source: PositionSource,
+}
+
+impl Position {
+    /// Sanity-check decoded values before using them in PnL or risk math
+    ///
+    /// Catches corrupt reads, e.g. from a storage layout change after a
+    /// contract upgrade.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            (MIN_LEVERAGE..=MAX_LEVERAGE).contains(&self.leverage),
+            "Position leverage {} outside {}..={}",
+            self.leverage,
+            MIN_LEVERAGE,
+            MAX_LEVERAGE
+        );
+        ensure!(
+            self.collateral.is_finite() && self.collateral >= 0.0,
+            "Position collateral {} is invalid",
+            self.collateral
+        );
+        ensure!(
+            self.open_price.is_finite() && self.open_price > 0.0,
+            "Position open price {} is not positive",
+            self.open_price
+        );
+        for (name, price) in [
+            ("take profit", self.take_profit),
+            ("stop loss", self.stop_loss),
+        ] {
+            if let Some(price) = price {
+                ensure!(
+                    price.is_finite() && price > 0.0,
+                    "Position {} {} is not positive",
+                    name,
+                    price
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize as one NDJSON record: the position's fields plus the
+    /// snapshot's Unix `timestamp` and `block_number`, without a trailing newline
+    pub fn to_json_line(&self, timestamp: u64, block_number: u64) -> Result<String> {
+        #[derive(Serialize)]
+        struct Record<'a> {
+            timestamp: u64,
+            block_number: u64,
+            #[serde(flatten)]
+            position: &'a Position,
+        }
+
+        Ok(serde_json::to_string(&Record {
+            timestamp,
+            block_number,
+            position: self,
+        })?)
+    }
+
+    /// Time the position has been open as of `now` (Unix timestamp)
+    pub fn age(&self, now: u64) -> Duration {
+        Duration::from_secs(now.saturating_sub(self.opened_at))
+    }
+
+    /// Position notional in USDC (collateral * leverage)
+    pub fn notional(&self) -> f64 {
+        self.collateral * self.leverage
+    }
+
+    /// Unrealized PnL in USDC at the given price, before fees
+    pub fn pnl_at(&self, current_price: f64) -> f64 {
+        if self.open_price <= 0.0 {
+            return 0.0;
+        }
+        let price_change = (current_price - self.open_price) / self.open_price;
+        let direction = if self.is_long { 1.0 } else { -1.0 };
+        self.notional() * price_change * direction
+    }
+
+    /// Leverage on current equity: notional / (collateral + unrealized PnL)
+    ///
+    /// Drifts from the opening `leverage` as the price moves; this is what
+    /// governs liquidation risk. Returns infinity once equity is wiped out.
+    pub fn effective_leverage(&self, current_price: f64) -> f64 {
+        let equity = self.collateral + self.pnl_at(current_price);
+        if equity <= 0.0 {
+            return f64::INFINITY;
+        }
+        self.notional() / equity
+    }
+
+    /// Unrealized PnL as a percentage of collateral (ROI), before fees
+    ///
+    /// Leverage amplifies the raw price move, so a 1% favorable move on a 10x
+    /// position yields ~10% ROI.
+    pub fn pnl_percent(&self, current_price: f64) -> f64 {
+        if self.collateral <= 0.0 {
+            return 0.0;
+        }
+        self.pnl_at(current_price) / self.collateral * 100.0
+    }
+
+    /// Price at which closing nets zero PnL after fees and funding (all in USDC)
+    ///
+    /// `funding` is the accrued funding/rollover cost (negative if received).
+    /// A long breaks even above its open price by the total fee burden, a
+    /// short below it.
+    pub fn break_even_price(&self, open_fee: f64, close_fee: f64, funding: f64) -> f64 {
+        let notional = self.notional();
+        if notional <= 0.0 {
+            return self.open_price;
+        }
+        let cost_fraction = (open_fee + close_fee + funding) / notional;
+        let direction = if self.is_long { 1.0 } else { -1.0 };
+        self.open_price * (1.0 + direction * cost_fraction)
+    }
+
+    /// Percentage price move from `current_price` still needed to hit take profit
+    ///
+    /// Positive while the target is ahead in the position's favor, negative
+    /// once the price has moved past it. `None` when no take profit is set.
+    pub fn distance_to_tp(&self, current_price: f64) -> Option<f64> {
+        let tp = self.take_profit?;
+        let direction = if self.is_long { 1.0 } else { -1.0 };
+        (current_price > 0.0).then(|| (tp - current_price) / current_price * 100.0 * direction)
+    }
+
+    /// Percentage adverse price move from `current_price` that would hit stop loss
+    ///
+    /// Positive while the stop is still ahead, negative once the price has
+    /// moved past it. `None` when no stop loss is set.
+    pub fn distance_to_sl(&self, current_price: f64) -> Option<f64> {
+        let sl = self.stop_loss?;
+        let direction = if self.is_long { 1.0 } else { -1.0 };
+        (current_price > 0.0).then(|| (current_price - sl) / current_price * 100.0 * direction)
+    }
+
+    /// Fraction (0-1) of the way from open price to take profit
+    ///
+    /// Clamped, so an adverse move reads 0 and a passed target reads 1.
+    /// `None` when no take profit is set or it equals the open price.
+    pub fn progress_to_tp(&self, current_price: f64) -> Option<f64> {
+        let tp = self.take_profit?;
+        let journey = tp - self.open_price;
+        (journey != 0.0).then(|| ((current_price - self.open_price) / journey).clamp(0.0, 1.0))
+    }
+
+    /// Price at which the position is liquidated
+    ///
+    /// Liquidation happens once losses reach [`LIQUIDATION_THRESHOLD_PERCENT`]
+    /// of collateral. Fees, funding and rollover are not included, so the
+    /// on-chain trigger sits slightly closer to the current price.
+    pub fn liquidation_price(&self) -> f64 {
+        liquidation_price(self.open_price, self.leverage, self.is_long)
+    }
+
+    /// Share of the liquidation buffer left at the given price
+    ///
+    /// 1.0 at zero PnL, above 1.0 in profit, and 0.0 once losses reach
+    /// [`LIQUIDATION_THRESHOLD_PERCENT`] of collateral. Like
+    /// [`Self::liquidation_price`], fees and funding are not included.
+    pub fn health(&self, current_price: f64) -> f64 {
+        let buffer = self.collateral * LIQUIDATION_THRESHOLD_PERCENT / 100.0;
+        if buffer <= 0.0 {
+            return 0.0;
+        }
+        ((buffer + self.pnl_at(current_price)) / buffer).max(0.0)
+    }
+
+    /// Params to fully close this position at the given market price
+    pub fn to_close_params(&self, market_price: f64) -> CloseTradeParams {
+        CloseTradeParams::close_all(self.pair_index, self.trade_index, market_price)
+    }
+
+    /// Params to close `close_percentage` percent (e.g. 50.0) of this position
+    pub fn to_partial_close_params(
+        &self,
+        close_percentage: f64,
+        market_price: f64,
+    ) -> CloseTradeParams {
+        CloseTradeParams {
+            close_percentage,
+            ..self.to_close_params(market_price)
+        }
+    }
+}
+
+/// Result of a position scan that tolerates per-pair read failures
+#[derive(Debug, Default)]
+pub struct PositionScanResult {
+    /// Positions read successfully, ordered by pair and trade index
+    pub positions: Vec<Position>,
+    /// Pairs that couldn't be read, with the error (typed errors such as
+    /// [`OstiumError::Network`] can be recovered with `downcast_ref`)
+    pub errors: Vec<(u16, eyre::Report)>,
+}
+
+impl PositionScanResult {
+    /// Whether every pair was read
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Indices of the pairs that couldn't be read
+    pub fn failed_pairs(&self) -> Vec<u16> {
+        self.errors
+            .iter()
+            .map(|(pair_index, _)| *pair_index)
+            .collect()
+    }
+
+    /// Return the positions, or the first pair's error with context naming
+    /// every failed pair
+    pub fn into_positions(mut self) -> Result<Vec<Position>> {
+        if self.errors.is_empty() {
+            return Ok(self.positions);
+        }
+        let failed = self.failed_pairs();
+        let (pair_index, error) = self.errors.remove(0);
+        Err(error.wrap_err(format!(
+            "Failed to read positions for {} pair(s) {:?}; first error on pair {}",
+            failed.len(),
+            failed,
+            pair_index
+        )))
+    }
+}
+
+/// Price at which a position opened at `open_price` is liquidated
+fn liquidation_price(open_price: f64, leverage: f64, is_long: bool) -> f64 {
+    if leverage <= 0.0 {
+        return 0.0;
+    }
+    let max_move = LIQUIDATION_THRESHOLD_PERCENT / 100.0 / leverage;
+    let direction = if is_long { 1.0 } else { -1.0 };
+    (open_price * (1.0 - direction * max_move)).max(0.0)
+}
+
+/// Open positions at a point in time, keyed by protocol trade ID
+#[derive(Debug, Clone, Default)]
+pub struct PositionSnapshot {
+    /// Positions by trade ID
+    pub positions: HashMap<U256, Position>,
+}
+
+impl PositionSnapshot {
+    /// Build a snapshot from a list of positions
+    pub fn new(positions: Vec<Position>) -> Self {
+        Self {
+            positions: positions
+                .into_iter()
+                .map(|position| (position.trade_id, position))
+                .collect(),
+        }
+    }
+
+    /// Number of positions in the snapshot
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether the snapshot has no positions
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Look up a position by trade ID
+    pub fn get(&self, trade_id: U256) -> Option<&Position> {
+        self.positions.get(&trade_id)
+    }
+}
+
+/// Changes between two [`PositionSnapshot`]s
+#[derive(Debug, Clone, Default)]
+pub struct PositionDiff {
+    /// Positions only in the newer snapshot
+    pub opened: Vec<Position>,
+    /// Positions only in the older snapshot
+    pub closed: Vec<Position>,
+    /// Positions in both whose collateral, leverage, open price or TP/SL
+    /// changed, as `(old, new)`
+    pub modified: Vec<(Position, Position)>,
+}
+
+impl PositionDiff {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.closed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Categorize the changes from `old` to `new` by trade ID
+///
+/// Unrealized PnL is ignored, so price moves alone don't count as a
+/// modification. Results are sorted by pair and trade index.
+pub fn diff_positions(old: &PositionSnapshot, new: &PositionSnapshot) -> PositionDiff {
+    let mut diff = PositionDiff::default();
+
+    for (trade_id, position) in &new.positions {
+        match old.get(*trade_id) {
+            None => diff.opened.push(position.clone()),
+            Some(previous) if position_changed(previous, position) => {
+                diff.modified.push((previous.clone(), position.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    diff.closed = old
+        .positions
+        .iter()
+        .filter(|(trade_id, _)| new.get(**trade_id).is_none())
+        .map(|(_, position)| position.clone())
+        .collect();
+
+    let key = |position: &Position| (position.pair_index, position.trade_index);
+    diff.opened.sort_by_key(key);
+    diff.closed.sort_by_key(key);
+    diff.modified.sort_by_key(|(_, position)| key(position));
+    diff
+}
+
+/// Whether the trade's on-chain parameters differ between two reads
+fn position_changed(old: &Position, new: &Position) -> bool {
+    old.collateral != new.collateral
+        || old.leverage != new.leverage
+        || old.open_price != new.open_price
+        || old.take_profit != new.take_profit
+        || old.stop_loss != new.stop_loss
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_fees() {
+        // $100 at 10x = $1000 notional, 0.05% fee = $0.50 each way
+        let fees = TradeFees::new(100.0, 10.0, 0.05);
+        assert!((fees.open_fee - 0.5).abs() < 1e-12);
+        assert!((fees.close_fee - 0.5).abs() < 1e-12);
+        assert!((fees.effective_collateral - 99.5).abs() < 1e-12);
+        assert!((fees.total() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_long_short_constructors() {
+        let long = PlaceOrderParams::long(1, 10.0, 5.0);
+        let short = PlaceOrderParams::short(1, 10.0, 5.0);
+        assert!(long.is_long);
+        assert!(!short.is_long);
+        assert_eq!(short.order_type, OrderType::Market);
+        assert_eq!(short.leverage, 5.0);
+    }
+
+    fn price_sample(bid: f64, ask: f64) -> PriceData {
+        PriceData {
+            from: "BTC".into(),
+            to: "USD".into(),
+            bid,
+            mid: (bid + ask) / 2.0,
+            ask,
+            is_market_open: true,
+            is_day_trading_closed: false,
+        }
+    }
+
+    #[test]
+    fn test_auto_slippage_resolution() {
+        let mode = SlippageMode::Auto {
+            floor: 0.5,
+            ceiling: 3.0,
+        };
+
+        // Calm market: tiny spread and no movement clamps to the floor
+        let calm = [price_sample(999.9, 1000.1), price_sample(999.9, 1000.1)];
+        assert_eq!(mode.resolve(&calm), Some(0.5));
+
+        // 0.2% spread + 2 * 0.5% range = 1.2%
+        let moving = [price_sample(994.0, 996.0), price_sample(999.0, 1001.0)];
+        assert!((mode.resolve(&moving).unwrap() - 1.2).abs() < 1e-9);
+
+        // Volatile market clamps to the ceiling
+        let wild = [price_sample(900.0, 902.0), price_sample(999.0, 1001.0)];
+        assert_eq!(mode.resolve(&wild), Some(3.0));
+
+        assert_eq!(SlippageMode::Fixed.resolve(&calm), None);
+
+        // Unresolved auto params encode the ceiling
+        let params = PlaceOrderParams::long(0, 10.0, 5.0).with_auto_slippage(0.5, 3.0);
+        assert_eq!(params.scaled_slippage(), U256::from(300));
+    }
+
+    #[test]
+    fn test_scaled_getters_match_trade() {
+        let params = PlaceOrderParams::market(0, 12.5, 25.0, true);
+        let trade = params.to_trade(Address::ZERO, 0);
+
+        assert_eq!(params.scaled_collateral(), U256::from(12_500_000u64));
+        assert_eq!(params.scaled_leverage(), 2_500);
+        assert_eq!(trade.collateral, params.scaled_collateral());
+        assert_eq!(trade.leverage, params.scaled_leverage());
+    }
+
+    #[test]
+    fn test_encode_open_calldata_round_trip() {
+        let trader = Address::repeat_byte(0x11);
+        let params = PlaceOrderParams::market(3, 25.0, 20.0, false)
+            .with_open_price(2_500.0)
+            .with_slippage(1.0);
+
+        let data = params.encode_calldata(trader, 1, None);
+        let decoded = ITrading::openTradeCall::abi_decode(&data).unwrap();
+
+        assert_eq!(decoded.t.trader, trader);
+        assert_eq!(decoded.t.pairIndex, 3);
+        assert_eq!(decoded.t.index, 1);
+        assert_eq!(decoded.t.collateral, scale_usdc(25.0));
+        assert_eq!(decoded.t.leverage, 2_000);
+        assert!(!decoded.t.buy);
+        assert_eq!(decoded.bf.builder, Address::ZERO);
+        assert_eq!(decoded.orderType, 0);
+        assert_eq!(decoded.slippageP, U256::from(100));
+    }
+
+    pub(crate) fn test_position(is_long: bool) -> Position {
+        Position {
+            trader: Address::ZERO,
+            pair_index: 0,
+            trade_index: 0,
+            collateral: 100.0,
+            leverage: 10.0,
+            is_long,
+            open_price: 50_000.0,
+            take_profit: None,
+            stop_loss: None,
+            unrealized_pnl: None,
+            trade_id: U256::from(1),
+            opened_at: 1_700_000_000,
+            opened_block: 1,
+            source: PositionSource::Contract,
+        }
+    }
+
+    #[test]
+    fn test_position_pnl_percent() {
+        // 1% favorable move on a 10x long is ~10% ROI
+        let long = test_position(true);
+        assert!((long.pnl_at(50_500.0) - 10.0).abs() < 1e-9);
+        assert!((long.pnl_percent(50_500.0) - 10.0).abs() < 1e-9);
+
+        // Same move against a 10x short is ~-10% ROI
+        let short = test_position(false);
+        assert!((short.pnl_percent(50_500.0) + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_position_age() {
+        let position = test_position(true);
+        let now = position.opened_at + 3 * 86_400 + 4 * 3_600;
+        assert_eq!(position.age(now), Duration::from_secs(273_600));
+        // Clock skew never yields a negative age
+        assert_eq!(position.age(0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_position_validate() {
+        assert!(test_position(true).validate().is_ok());
+
+        let mut zero_leverage = test_position(true);
+        zero_leverage.leverage = 0.0;
+        assert!(zero_leverage.validate().is_err());
+
+        let mut bad_price = test_position(false);
+        bad_price.open_price = 0.0;
+        assert!(bad_price.validate().is_err());
+
+        let mut bad_tp = test_position(true);
+        bad_tp.take_profit = Some(-1.0);
+        assert!(bad_tp.validate().is_err());
+    }
+
+    #[test]
+    fn test_distance_to_targets() {
+        let mut long = test_position(true);
+        assert_eq!(long.distance_to_tp(50_000.0), None);
+        assert_eq!(long.progress_to_tp(50_000.0), None);
+        long.take_profit = Some(55_000.0);
+        long.stop_loss = Some(45_000.0);
+        assert!((long.distance_to_tp(50_000.0).unwrap() - 10.0).abs() < 1e-9);
+        assert!((long.distance_to_sl(50_000.0).unwrap() - 10.0).abs() < 1e-9);
+        assert!((long.progress_to_tp(52_500.0).unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(long.progress_to_tp(48_000.0), Some(0.0));
+        assert!(long.distance_to_tp(56_000.0).unwrap() < 0.0);
+
+        // A short's targets sit on the other side of the price
+        let mut short = test_position(false);
+        short.take_profit = Some(45_000.0);
+        short.stop_loss = Some(55_000.0);
+        assert!((short.distance_to_tp(50_000.0).unwrap() - 10.0).abs() < 1e-9);
+        assert!((short.distance_to_sl(50_000.0).unwrap() - 10.0).abs() < 1e-9);
+        assert!((short.progress_to_tp(47_500.0).unwrap() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_position_health() {
+        let long = test_position(true);
+        assert!((long.health(50_000.0) - 1.0).abs() < 1e-9);
+        // A 4.5% drop on 10x loses 45 of the 90 USDC buffer
+        assert!((long.health(47_750.0) - 0.5).abs() < 1e-9);
+        assert_eq!(long.health(long.liquidation_price() * 0.99), 0.0);
+        assert!(long.health(51_000.0) > 1.0);
+    }
+
+    #[test]
+    fn test_break_even_price() {
+        // $1000 notional paying $1 total fees must move 0.1% to break even
+        let long = test_position(true);
+        assert!((long.break_even_price(0.5, 0.5, 0.0) - 50_050.0).abs() < 1e-6);
+        assert!((long.pnl_at(long.break_even_price(0.5, 0.5, 0.0)) - 1.0).abs() < 1e-9);
+
+        let short = test_position(false);
+        assert!((short.break_even_price(0.5, 0.5, 0.0) - 49_950.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_effective_leverage() {
+        let long = test_position(true);
+        assert!((long.effective_leverage(50_000.0) - 10.0).abs() < 1e-9);
+        // A 5% drop halves equity on a 10x long, doubling effective leverage
+        assert!((long.effective_leverage(47_500.0) - 20.0).abs() < 1e-9);
+        assert!(long.effective_leverage(52_500.0) < 10.0);
+        assert!(long.effective_leverage(45_000.0).is_infinite());
+    }
+
+    #[test]
+    fn test_collateral_preview() {
+        // 100 USDC at 10x from 50k: liquidated after a 9% adverse move
+        let long = test_position(true);
+        assert!((long.liquidation_price() - 45_500.0).abs() < 1e-6);
+        assert!((test_position(false).liquidation_price() - 54_500.0).abs() < 1e-6);
+
+        let added = CollateralPreview::new(&long, 100.0).unwrap();
+        assert!((added.leverage - 5.0).abs() < 1e-9);
+        assert!((added.liquidation_price - 41_000.0).abs() < 1e-6);
+        assert!((added.current_liquidation_price - 45_500.0).abs() < 1e-6);
+
+        let removed = CollateralPreview::new(&long, -50.0).unwrap();
+        assert!((removed.leverage - 20.0).abs() < 1e-9);
+        assert!(removed.liquidation_price > long.liquidation_price());
+
+        assert!(CollateralPreview::new(&long, -100.0).is_err());
+        assert!(CollateralPreview::new(&long, 1_000.0).is_err());
+    }
+
+    #[test]
+    fn test_diff_positions() {
+        let position = |trade_id: u64, trade_index| Position {
+            trade_id: U256::from(trade_id),
+            trade_index,
+            ..test_position(true)
+        };
+        let kept = position(1, 0);
+        let closed = position(2, 1);
+        let modified = position(3, 2);
+        let old = PositionSnapshot::new(vec![kept.clone(), closed, modified.clone()]);
+
+        let mut topped_up = modified;
+        topped_up.collateral = 150.0;
+        let mut repriced = kept;
+        repriced.unrealized_pnl = Some(12.0);
+        let new = PositionSnapshot::new(vec![repriced, topped_up, position(4, 3)]);
+
+        let diff = diff_positions(&old, &new);
+        assert_eq!(diff.opened.len(), 1);
+        assert_eq!(diff.opened[0].trade_id, U256::from(4));
+        assert_eq!(diff.closed.len(), 1);
+        assert_eq!(diff.closed[0].trade_id, U256::from(2));
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].1.collateral, 150.0);
+
+        assert!(diff_positions(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_order_params_serde_round_trip() {
+        let params = PlaceOrderParams::short(1, 25.0, 5.0)
+            .with_open_price(3_000.0)
+            .with_auto_slippage(0.5, 3.0)
+            .with_trader(Address::repeat_byte(7));
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["order_type"], "market");
+        assert_eq!(json["slippage_mode"]["auto"]["ceiling"], 3.0);
+
+        let restored: PlaceOrderParams = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.slippage_mode, params.slippage_mode);
+        assert_eq!(restored.trader, params.trader);
+        assert_eq!(restored.open_price, Some(3_000.0));
+
+        // Omitted fields fall back to defaults
+        let minimal: PlaceOrderParams =
+            serde_json::from_str(r#"{"pair_index":0,"collateral":10.0,"order_type":"limitopen"}"#)
+                .unwrap();
+        assert_eq!(minimal.order_type, OrderType::LimitOpen);
+        assert_eq!(minimal.leverage, 10.0);
+
+        let close = CloseTradeParams::close_all(0, 1, 50_000.0).with_slippage(1.0);
+        let restored: CloseTradeParams =
+            serde_json::from_str(&serde_json::to_string(&close).unwrap()).unwrap();
+        assert_eq!(restored.scaled_slippage(), close.scaled_slippage());
+    }
+
+    #[test]
+    fn test_market_symbol_requires_resolution() {
+        let mut params = PlaceOrderParams::market_symbol("BTC", "USD", 100.0, 10.0, true);
+        assert_eq!(
+            params.pair_symbol,
+            Some(("BTC".to_string(), "USD".to_string()))
+        );
+        assert!(params
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("BTC/USD"));
+
+        params.pair_symbol = None;
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_check_min_collateral() {
+        let params = PlaceOrderParams::long(5, 100.0, 10.0);
+        assert!(params.check_min_collateral(100.0).is_ok());
+        let err = params.check_min_collateral(150.0).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("below the minimum of 150 USDC for pair 5"));
+    }
+
+    #[test]
+    fn test_position_to_json_line() {
+        let line = test_position(true).to_json_line(1_700_000_100, 42).unwrap();
+        assert!(!line.contains('\n'));
+
+        let record: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(record["timestamp"], 1_700_000_100u64);
+        assert_eq!(record["block_number"], 42);
+        assert_eq!(record["collateral"], 100.0);
+        assert_eq!(record["is_long"], true);
+        assert_eq!(record["source"], "contract");
+        assert!(record["take_profit"].is_null());
+    }
+
+    #[test]
+    fn test_position_to_close_params() {
+        let mut position = test_position(true);
+        position.pair_index = 1;
+        position.trade_index = 2;
+
+        let full = position.to_close_params(3_000.0);
+        assert_eq!((full.pair_index, full.trade_index), (1, 2));
+        assert_eq!(full.scaled_close_percentage(), 10_000);
+
+        let half = position.to_partial_close_params(50.0, 3_000.0);
+        assert_eq!(half.scaled_close_percentage(), 5_000);
+        assert_eq!(half.market_price, 3_000.0);
+
+        // 0.29 * 100 is 28.999... and must not truncate to 28
+        let sliver = position.to_partial_close_params(0.29, 3_000.0);
+        assert_eq!(sliver.scaled_close_percentage(), 29);
+    }
+
+    #[test]
+    fn test_protocol_limits_check_index() {
+        let limits = ProtocolLimits {
+            pairs_count: 10,
+            max_trades_per_pair: 3,
+        };
+        assert!(limits.check_index(9, 2).is_ok());
+        assert_eq!(limits.available_slots(0), 3);
+        assert_eq!(limits.available_slots(2), 1);
+        assert_eq!(limits.available_slots(5), 0);
+
+        for err in [limits.check_index(10, 0), limits.check_index(0, 3)] {
+            assert!(matches!(
+                err.unwrap_err().downcast_ref::<OstiumError>(),
+                Some(OstiumError::InvalidIndex(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_slippage_recommendation() {
+        // Small order on an uncapped pair: spread plus buffer
+        let small = SlippageRecommendation::new(0.04, 1_000.0, None);
+        assert!((small.slippage_percent - 0.14).abs() < 1e-9);
+        assert_eq!(small.depth_ratio, 0.0);
+
+        // Taking a quarter of the remaining OI adds a quarter of the depth impact
+        let large = SlippageRecommendation::new(0.04, 250_000.0, Some(1_000_000.0));
+        assert!((large.slippage_percent - 0.64).abs() < 1e-9);
+
+        // A full side caps the depth ratio
+        let full = SlippageRecommendation::new(0.04, 10.0, Some(0.0));
+        assert_eq!(full.depth_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_order_worst_case_price() {
+        let long = PlaceOrderParams::long(0, 100.0, 10.0).with_slippage(1.0);
+        assert!((long.worst_case_price(100.0) - 101.0).abs() < 1e-9);
+        let short = PlaceOrderParams::short(0, 100.0, 10.0).with_slippage(1.0);
+        assert!((short.worst_case_price(100.0) - 99.0).abs() < 1e-9);
+        assert_eq!(long.liquidation_price(50_000.0), 45_500.0);
+    }
+
+    #[test]
+    fn test_close_result() {
+        let gain = CloseResult::new(TxHash::ZERO, None, 50.0, 54.2);
+        assert!((gain.realized_pnl - 4.2).abs() < 1e-9);
+        // Liquidated or wiped out: nothing paid back
+        let wiped = CloseResult::new(TxHash::ZERO, None, 50.0, 0.0);
+        assert_eq!(wiped.realized_pnl, -50.0);
+    }
+
+    #[test]
+    fn test_close_quote() {
+        // Half of a 10x long closed after a 1% rise, at a 0.05% fee
+        let position = test_position(true);
+        let quote = CloseQuote::new(&position, 50.0, 50_500.0, 0.05);
+
+        assert!((quote.closed_collateral - 50.0).abs() < 1e-9);
+        assert!((quote.gross_pnl - 5.0).abs() < 1e-9);
+        assert!((quote.close_fee - 0.25).abs() < 1e-9);
+        assert!((quote.net_pnl - 4.75).abs() < 1e-9);
+        assert!((quote.proceeds - 54.75).abs() < 1e-9);
+
+        // A loss beyond the collateral never quotes negative proceeds
+        let wiped = CloseQuote::new(&position, 100.0, 40_000.0, 0.05);
+        assert_eq!(wiped.proceeds, 0.0);
+    }
+
+    #[test]
+    fn test_close_params_encoding_is_direction_independent() {
+        // Long and short closes encode the same fields; the contract derives the bound
+        let params = CloseTradeParams::close_all(0, 1, 50_000.0).with_slippage(1.5);
+
+        assert_eq!(params.scaled_close_percentage(), 10_000);
+        assert_eq!(
+            params.scaled_market_price(),
+            u256_to_u192(scale_price(50_000.0))
+        );
+        assert_eq!(params.scaled_slippage(), 150);
+    }
+
+    #[test]
+    fn test_close_worst_case_price_by_direction() {
+        let params = CloseTradeParams::close_all(0, 0, 50_000.0).with_slippage(2.0);
+
+        // Long close sells: worst case is below market
+        assert!((params.worst_case_price(true) - 49_000.0).abs() < 1e-6);
+        // Short close buys: worst case is above market
+        assert!((params.worst_case_price(false) - 51_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_close_params_validate() {
+        let params = |close_percentage| CloseTradeParams {
+            close_percentage,
+            ..CloseTradeParams::close_all(0, 0, 50_000.0)
+        };
+        assert!(params(100.0).validate().is_ok());
+        assert!(params(0.01).validate().is_ok());
+        for close_percentage in [0.0, -10.0, 100.01, f64::NAN, f64::INFINITY] {
+            assert!(
+                params(close_percentage).validate().is_err(),
+                "{close_percentage}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_close_with_limit_price() {
+        let long = CloseTradeParams::close_all(0, 0, 66_000.0)
+            .with_limit_price(65_000.0, true)
+            .unwrap();
+        // 1.5152% floors to 151 bp, whose worst price stays above the limit
+        assert_eq!(long.scaled_slippage(), 151);
+        let encoded_worst = 66_000.0 * (1.0 - long.scaled_slippage() as f64 / 10_000.0);
+        assert!(encoded_worst >= 65_000.0);
+        assert!(long.worst_case_price(true) >= 65_000.0);
+
+        let short = CloseTradeParams::close_all(0, 0, 100.0)
+            .with_limit_price(102.0, false)
+            .unwrap();
+        assert_eq!(short.scaled_slippage(), 200);
+        assert!((short.worst_case_price(false) - 102.0).abs() < 1e-9);
+
+        // Limit on the wrong side of the market for the direction
+        let params = CloseTradeParams::close_all(0, 0, 100.0);
+        assert!(params.clone().with_limit_price(101.0, true).is_err());
+        assert!(params.clone().with_limit_price(99.0, false).is_err());
+        // Too far from the market
+        assert!(params.with_limit_price(250.0, false).is_err());
+    }
+
+    #[test]
+    fn test_open_and_close_slippage_encode_identically() {
+        for percent in [0.5, 1.0, 2.0, 2.5, 7.25] {
+            let open = PlaceOrderParams::market(0, 100.0, 10.0, true).with_slippage(percent);
+            let close = CloseTradeParams::close_all(0, 0, 100.0).with_slippage(percent);
+            assert_eq!(open.scaled_slippage(), U256::from(close.scaled_slippage()));
+        }
+        let close = CloseTradeParams::close_all(0, 0, 100.0).with_slippage(2.5);
+        assert_eq!(close.scaled_slippage(), 250);
+    }
+
+    #[test]
+    fn test_position_scan_result() {
+        let complete = PositionScanResult {
+            positions: vec![test_position(true)],
+            errors: Vec::new(),
+        };
+        assert!(complete.is_complete());
+        assert_eq!(complete.into_positions().unwrap().len(), 1);
+
+        let partial = PositionScanResult {
+            positions: vec![test_position(true)],
+            errors: vec![
+                (3, eyre::Report::new(OstiumError::Network("timeout".into()))),
+                (37, eyre::eyre!("rate limited")),
+            ],
+        };
+        assert!(!partial.is_complete());
+        assert_eq!(partial.failed_pairs(), vec![3, 37]);
+        let err = partial.into_positions().unwrap_err();
+        assert!(format!("{:#}", err).contains("[3, 37]"));
+        assert!(matches!(
+            err.downcast_ref::<OstiumError>(),
+            Some(OstiumError::Network(_))
+        ));
+    }
+
+    mod props {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn open_calldata_round_trips(
+                pair_index in any::<u16>(),
+                trade_index in 0u8..3,
+                collateral_micros in 1u64..1_000_000_000_000,
+                leverage_scaled in 200u32..=100_000,
+                is_long in any::<bool>(),
+                price in 0.000_001f64..10_000_000.0,
+                trader in any::<[u8; 20]>(),
+            ) {
+                let trader = Address::from(trader);
+                let params = PlaceOrderParams::market(
+                    pair_index,
+                    collateral_micros as f64 / 1e6,
+                    leverage_scaled as f64 / 100.0,
+                    is_long,
+                )
+                .with_open_price(price);
+
+                let data = params.encode_calldata(trader, trade_index, None);
+                let decoded = ITrading::openTradeCall::abi_decode(&data).unwrap();
+                let expected = params.to_trade(trader, trade_index);
+
+                prop_assert_eq!(decoded.t.trader, trader);
+                prop_assert_eq!(decoded.t.pairIndex, pair_index);
+                prop_assert_eq!(decoded.t.index, trade_index);
+                prop_assert_eq!(decoded.t.buy, is_long);
+                prop_assert_eq!(decoded.t.collateral, U256::from(collateral_micros));
+                prop_assert_eq!(decoded.t.leverage, expected.leverage);
+                prop_assert_eq!(decoded.t.openPrice, expected.openPrice);
+                prop_assert_eq!(decoded.slippageP, params.scaled_slippage());
+            }
+        }
+    }
 }
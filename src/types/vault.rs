@@ -1,15 +1,22 @@
 //! Vault types for user-facing API
 
-use crate::constants::{scale_usdc, unscale_from_decimals, USDC_DECIMALS};
-use alloy::primitives::{Address, U256};
+use crate::constants::{scale_usdc, unscale_from_decimals, MAX_DEPOSIT_AMOUNT, USDC_DECIMALS};
+use crate::contracts::IOstiumVault;
+use crate::error::OstiumError;
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::sol_types::SolCall;
+use eyre::{ensure, Report, Result};
+use serde::{Deserialize, Serialize};
 
 /// Parameters for depositing to OLP vault
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DepositParams {
     /// Amount of USDC to deposit
     pub amount: f64,
     /// Receiver address for OLP shares (defaults to sender)
     pub receiver: Option<Address>,
+    /// Deposit this percentage of the USDC balance instead of `amount`
+    pub balance_percent: Option<f64>,
 }
 
 impl DepositParams {
@@ -18,13 +25,142 @@ impl DepositParams {
         Self {
             amount,
             receiver: None,
+            balance_percent: None,
+        }
+    }
+
+    /// Create deposit params for a percentage (e.g. 50.0) of the USDC balance
+    ///
+    /// The amount is computed from the balance when depositing. 100% deposits
+    /// the exact balance with no dust; gas is paid in ETH, so no USDC is held back.
+    pub fn percent_of_balance(percent: f64) -> Self {
+        Self {
+            amount: 0.0,
+            receiver: None,
+            balance_percent: Some(percent),
+        }
+    }
+
+    /// Validate the amount or balance percentage
+    pub fn validate(&self) -> Result<()> {
+        match self.balance_percent {
+            Some(percent) => ensure!(
+                percent > 0.0 && percent <= 100.0,
+                "Balance percentage must be in (0, 100], got {}",
+                percent
+            ),
+            None => {
+                ensure!(
+                    self.amount.is_finite() && self.amount > 0.0,
+                    "Deposit amount must be positive"
+                );
+                ensure!(
+                    self.amount <= MAX_DEPOSIT_AMOUNT,
+                    "Deposit amount {} exceeds {} USDC; amounts are in USDC, not raw units",
+                    self.amount,
+                    MAX_DEPOSIT_AMOUNT
+                );
+            }
         }
+        Ok(())
     }
 
     /// Get scaled USDC amount
     pub fn scaled_amount(&self) -> U256 {
         scale_usdc(self.amount)
     }
+
+    /// Raw USDC amount to deposit given the depositor's raw USDC balance
+    ///
+    /// Percentages are applied in integer basis points and rounded down, so
+    /// the result never exceeds `balance`.
+    pub fn resolve_amount(&self, balance: U256) -> U256 {
+        match self.balance_percent {
+            Some(percent) if percent >= 100.0 => balance,
+            Some(percent) => {
+                let basis_points = (percent * 100.0).round() as u64;
+                balance * U256::from(basis_points) / U256::from(10_000u64)
+            }
+            None => self.scaled_amount(),
+        }
+    }
+
+    /// Encode vault `deposit` calldata
+    ///
+    /// `default_receiver` is used when no receiver was set on the params.
+    pub fn encode_calldata(&self, default_receiver: Address) -> Bytes {
+        self.encode_calldata_for(self.scaled_amount(), default_receiver)
+    }
+
+    /// Encode vault `deposit` calldata for a resolved raw USDC amount
+    pub fn encode_calldata_for(&self, assets: U256, default_receiver: Address) -> Bytes {
+        let call = IOstiumVault::depositCall {
+            assets,
+            receiver: self.receiver.unwrap_or(default_receiver),
+        };
+        Bytes::from(call.abi_encode())
+    }
+}
+
+/// Parameters for redeeming OLP shares
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedeemParams {
+    /// OLP shares to redeem (raw value in the OLP token's decimals)
+    pub shares: U256,
+    /// Recipient of the USDC (defaults to the owner)
+    pub receiver: Option<Address>,
+    /// Redeem the whole balance instead of failing when `shares` exceeds it
+    pub clamp_to_balance: bool,
+}
+
+impl RedeemParams {
+    /// Create redeem params for a raw share amount
+    pub fn new(shares: U256) -> Self {
+        Self {
+            shares,
+            receiver: None,
+            clamp_to_balance: false,
+        }
+    }
+
+    /// Send the USDC to another address
+    pub fn with_receiver(mut self, receiver: Address) -> Self {
+        self.receiver = Some(receiver);
+        self
+    }
+
+    /// Cap the shares to the owner's balance instead of failing
+    pub fn clamp_to_balance(mut self) -> Self {
+        self.clamp_to_balance = true;
+        self
+    }
+
+    /// Validate the share amount
+    ///
+    /// Zero shares would revert on-chain, so they are rejected up front.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(!self.shares.is_zero(), "Redeem shares must be positive");
+        Ok(())
+    }
+
+    /// Shares to redeem given the owner's raw share balance
+    ///
+    /// Errors with [`OstiumError::InsufficientShares`] when `shares` exceeds
+    /// the balance, unless clamping, and when there is nothing to redeem.
+    pub fn resolve_shares(&self, balance: U256) -> Result<U256> {
+        let shares = if self.shares > balance {
+            if !self.clamp_to_balance {
+                return Err(Report::new(OstiumError::InsufficientShares {
+                    available: balance,
+                }));
+            }
+            balance
+        } else {
+            self.shares
+        };
+        ensure!(!shares.is_zero(), "No OLP shares to redeem");
+        Ok(shares)
+    }
 }
 
 /// User's OLP vault position
@@ -34,20 +170,29 @@ pub struct VaultPosition {
     pub shares: U256,
     /// Equivalent USDC value
     pub value: f64,
+    /// Decimals of the OLP share token
+    pub share_decimals: u8,
 }
 
 impl VaultPosition {
-    /// Create from raw values
+    /// Create from raw values, assuming 6-decimal shares like USDC
     pub fn new(shares: U256, assets: U256) -> Self {
         Self {
             shares,
             value: unscale_from_decimals(assets, USDC_DECIMALS),
+            share_decimals: USDC_DECIMALS,
         }
     }
 
-    /// Get shares as f64 (with 6 decimals)
+    /// Set the share token's decimals, as read from the vault
+    pub fn with_share_decimals(mut self, share_decimals: u8) -> Self {
+        self.share_decimals = share_decimals;
+        self
+    }
+
+    /// Get shares as f64, unscaled by the share token's decimals
     pub fn shares_f64(&self) -> f64 {
-        unscale_from_decimals(self.shares, USDC_DECIMALS)
+        unscale_from_decimals(self.shares, self.share_decimals)
     }
 }
 
@@ -63,3 +208,139 @@ pub struct VaultEpoch {
     /// Whether withdrawals are currently open (first 48h of epoch)
     pub withdrawals_open: bool,
 }
+
+/// How much of the OLP vault's capital backs open trades (USDC values)
+///
+/// The vault is the counterparty to every trade, so open interest is the
+/// exposure its assets back. Longs and shorts partly offset each other, which
+/// makes the net figure the vault's directional risk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultUtilization {
+    /// USDC held by the vault (`totalAssets`)
+    pub total_assets: f64,
+    /// Long open interest across all pairs, in USDC
+    pub long_oi: f64,
+    /// Short open interest across all pairs, in USDC
+    pub short_oi: f64,
+}
+
+impl VaultUtilization {
+    /// Total open interest across both sides
+    pub fn total_oi(&self) -> f64 {
+        self.long_oi + self.short_oi
+    }
+
+    /// Open interest not offset by the other side
+    pub fn net_oi(&self) -> f64 {
+        (self.long_oi - self.short_oi).abs()
+    }
+
+    /// Total open interest as a ratio of vault assets (0 if the vault is empty)
+    pub fn utilization(&self) -> f64 {
+        ratio(self.total_oi(), self.total_assets)
+    }
+
+    /// Net open interest as a ratio of vault assets (0 if the vault is empty)
+    pub fn net_utilization(&self) -> f64 {
+        ratio(self.net_oi(), self.total_assets)
+    }
+}
+
+fn ratio(value: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        value / total
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redeem_resolve_shares() {
+        let balance = U256::from(1_000_000u64);
+        let params = RedeemParams::new(U256::from(2_000_000u64));
+
+        let err = params.resolve_shares(balance).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OstiumError>(),
+            Some(&OstiumError::InsufficientShares { available: balance })
+        );
+        assert_eq!(
+            params.clamp_to_balance().resolve_shares(balance).unwrap(),
+            balance
+        );
+        assert_eq!(
+            RedeemParams::new(U256::from(5u64))
+                .resolve_shares(balance)
+                .unwrap(),
+            U256::from(5u64)
+        );
+        assert!(RedeemParams::new(U256::from(5u64))
+            .clamp_to_balance()
+            .resolve_shares(U256::ZERO)
+            .is_err());
+    }
+
+    #[test]
+    fn test_percent_of_balance() {
+        // 1,234.567891 USDC
+        let balance = U256::from(1_234_567_891u64);
+
+        let half = DepositParams::percent_of_balance(50.0);
+        assert_eq!(half.resolve_amount(balance), U256::from(617_283_945u64));
+
+        let all = DepositParams::percent_of_balance(100.0);
+        assert_eq!(all.resolve_amount(balance), balance);
+
+        // Fixed amounts ignore the balance
+        assert_eq!(
+            DepositParams::new(10.0).resolve_amount(balance),
+            U256::from(10_000_000u64)
+        );
+
+        assert!(DepositParams::percent_of_balance(0.0).validate().is_err());
+        assert!(DepositParams::percent_of_balance(100.5).validate().is_err());
+        assert!(half.validate().is_ok());
+    }
+
+    #[test]
+    fn test_vault_position_share_decimals() {
+        let position = VaultPosition::new(U256::from(1_500_000u64), U256::from(1_600_000u64));
+        assert_eq!(position.shares_f64(), 1.5);
+        assert_eq!(position.value, 1.6);
+        assert_eq!(position.with_share_decimals(18).shares_f64(), 1.5e-12);
+    }
+
+    #[test]
+    fn test_deposit_amount_validation() {
+        assert!(DepositParams::new(250.0).validate().is_ok());
+        assert!(DepositParams::new(MAX_DEPOSIT_AMOUNT).validate().is_ok());
+        for amount in [0.0, -5.0, f64::NAN, f64::INFINITY, MAX_DEPOSIT_AMOUNT * 2.0] {
+            assert!(DepositParams::new(amount).validate().is_err(), "{amount}");
+        }
+        assert!(RedeemParams::new(U256::ZERO).validate().is_err());
+        assert!(RedeemParams::new(U256::from(1u64)).validate().is_ok());
+    }
+
+    #[test]
+    fn test_vault_utilization() {
+        let utilization = VaultUtilization {
+            total_assets: 1_000_000.0,
+            long_oi: 600_000.0,
+            short_oi: 200_000.0,
+        };
+        assert_eq!(utilization.total_oi(), 800_000.0);
+        assert_eq!(utilization.net_oi(), 400_000.0);
+        assert!((utilization.utilization() - 0.8).abs() < 1e-12);
+        assert!((utilization.net_utilization() - 0.4).abs() < 1e-12);
+
+        let empty = VaultUtilization {
+            total_assets: 0.0,
+            ..utilization
+        };
+        assert_eq!(empty.utilization(), 0.0);
+    }
+}